@@ -0,0 +1,364 @@
+use std::io::{Error, ErrorKind, Result};
+
+use getset::Getters;
+
+use crate::{
+    interval::Tier as IntervalTier,
+    utilities::{interval_boundaries, match_times},
+};
+
+/// How well two transcribers agree on one label, measured over frames.
+#[derive(Clone, Debug, Getters)]
+pub struct LabelAgreement {
+    /// The label. Frames outside every interval count as the empty label.
+    #[getset(get = "pub")]
+    label: String,
+    /// The number of frames with this label in the reference tier.
+    #[getset(get = "pub")]
+    reference_frames: usize,
+    /// The number of frames with this label in the other tier.
+    #[getset(get = "pub")]
+    other_frames: usize,
+    /// The number of frames both tiers give this label.
+    #[getset(get = "pub")]
+    agreed_frames: usize,
+    /// The share of the other tier's frames with this label that the reference agrees with.
+    #[getset(get = "pub")]
+    precision: f64,
+    /// The share of the reference tier's frames with this label that the other tier agrees with.
+    #[getset(get = "pub")]
+    recall: f64,
+    /// The harmonic mean of `precision` and `recall`.
+    #[getset(get = "pub")]
+    f1: f64,
+}
+
+/// The agreement between two transcriptions of the same audio. Created by
+/// `IntervalTier::agreement`.
+///
+/// Ratios with nothing to count, such as the precision of a tier with no boundaries, are 1.
+#[derive(Clone, Debug, Getters)]
+pub struct AgreementReport {
+    /// The number of inner boundaries in the reference tier.
+    #[getset(get = "pub")]
+    reference_boundaries: usize,
+    /// The number of inner boundaries in the other tier.
+    #[getset(get = "pub")]
+    other_boundaries: usize,
+    /// The number of boundary pairs within the tolerance of each other.
+    #[getset(get = "pub")]
+    matched_boundaries: usize,
+    /// The share of the other tier's boundaries that match a reference boundary.
+    #[getset(get = "pub")]
+    boundary_precision: f64,
+    /// The share of the reference tier's boundaries that match a boundary of the other tier.
+    #[getset(get = "pub")]
+    boundary_recall: f64,
+    /// The harmonic mean of `boundary_precision` and `boundary_recall`.
+    #[getset(get = "pub")]
+    boundary_f1: f64,
+    /// The mean absolute distance between matched boundaries, or `None` if none matched.
+    #[getset(get = "pub")]
+    mean_boundary_displacement: Option<f64>,
+    /// The number of frames compared.
+    #[getset(get = "pub")]
+    frames: usize,
+    /// The share of frames with the same label in both tiers.
+    #[getset(get = "pub")]
+    observed_agreement: f64,
+    /// Cohen's kappa over the frame labels.
+    #[getset(get = "pub")]
+    cohens_kappa: f64,
+    /// Krippendorff's alpha for nominal data over the frame labels.
+    #[getset(get = "pub")]
+    krippendorffs_alpha: f64,
+    /// Every label seen in either tier, sorted. Indexes the rows and columns of `confusion`.
+    #[getset(get = "pub")]
+    labels: Vec<String>,
+    /// The frame counts by reference label (rows) and other label (columns).
+    #[getset(get = "pub")]
+    confusion: Vec<Vec<usize>>,
+    /// The frame agreement for each label, in the order of `labels`.
+    #[getset(get = "pub")]
+    per_label: Vec<LabelAgreement>,
+}
+
+impl IntervalTier {
+    /// Measures the agreement between this tier, as the reference, and another transcription
+    /// of the same audio.
+    ///
+    /// Boundaries are the interval edges strictly inside each tier, paired one to one in time
+    /// order when at most `tolerance` apart. Labels are compared over frames of `frame_step`
+    /// seconds across the time both tiers cover, each taking the label at its centre.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other transcription.
+    /// * `tolerance` - The furthest apart two boundaries can be and still match.
+    /// * `frame_step` - The length of each frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `tolerance` is negative, or if `frame_step` is not positive and
+    /// finite.
+    pub fn agreement(
+        &self,
+        other: &Self,
+        tolerance: f64,
+        frame_step: f64,
+    ) -> Result<AgreementReport> {
+        if tolerance < 0.0 || tolerance.is_nan() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Tolerance must not be negative, got {tolerance}"),
+            ));
+        }
+        if !(frame_step > 0.0 && frame_step.is_finite()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Frame step must be positive and finite, got {frame_step}"),
+            ));
+        }
+
+        let mut report = AgreementReport {
+            reference_boundaries: 0,
+            other_boundaries: 0,
+            matched_boundaries: 0,
+            boundary_precision: 1.0,
+            boundary_recall: 1.0,
+            boundary_f1: 1.0,
+            mean_boundary_displacement: None,
+            frames: 0,
+            observed_agreement: 1.0,
+            cohens_kappa: 1.0,
+            krippendorffs_alpha: 1.0,
+            labels: Vec::new(),
+            confusion: Vec::new(),
+            per_label: Vec::new(),
+        };
+        report.compare_boundaries(self, other, tolerance);
+        report.compare_labels(self, other, frame_step);
+
+        Ok(report)
+    }
+}
+
+impl AgreementReport {
+    fn compare_boundaries(
+        &mut self,
+        reference: &IntervalTier,
+        other: &IntervalTier,
+        tolerance: f64,
+    ) {
+        let inner = |tier: &IntervalTier| {
+            interval_boundaries(tier)
+                .into_iter()
+                .filter(|t| t > tier.xmin() && t < tier.xmax())
+                .collect::<Vec<f64>>()
+        };
+        let (reference, other) = (inner(reference), inner(other));
+        let matches = match_times(&reference, &other, tolerance);
+
+        self.reference_boundaries = reference.len();
+        self.other_boundaries = other.len();
+        self.matched_boundaries = matches.len();
+        self.boundary_precision = ratio(matches.len(), other.len());
+        self.boundary_recall = ratio(matches.len(), reference.len());
+        self.boundary_f1 = f1(self.boundary_precision, self.boundary_recall);
+
+        #[allow(clippy::cast_precision_loss)]
+        if !matches.is_empty() {
+            let total = matches
+                .iter()
+                .map(|&(i, j)| (reference[i] - other[j]).abs())
+                .sum::<f64>();
+            self.mean_boundary_displacement = Some(total / matches.len() as f64);
+        }
+    }
+
+    fn compare_labels(&mut self, reference: &IntervalTier, other: &IntervalTier, frame_step: f64) {
+        let start = reference.xmin().max(*other.xmin());
+        let end = reference.xmax().min(*other.xmax());
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let frames = ((end - start) / frame_step).floor().max(0.0) as usize;
+
+        let label_at = |tier: &IntervalTier, t: f64| {
+            tier.interval_at(t)
+                .map_or_else(String::new, |interval| interval.text().clone())
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let pairs = (0..frames)
+            .map(|k| {
+                let t = (k as f64 + 0.5).mul_add(frame_step, start);
+                (label_at(reference, t), label_at(other, t))
+            })
+            .collect::<Vec<(String, String)>>();
+
+        let mut labels = pairs
+            .iter()
+            .flat_map(|(a, b)| [a.clone(), b.clone()])
+            .collect::<Vec<String>>();
+        labels.sort();
+        labels.dedup();
+
+        let position = |label: &String| labels.binary_search(label).unwrap_or_default();
+        let mut confusion = vec![vec![0; labels.len()]; labels.len()];
+        for (a, b) in &pairs {
+            confusion[position(a)][position(b)] += 1;
+        }
+
+        self.per_label = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let reference_frames = confusion[i].iter().sum::<usize>();
+                let other_frames = confusion.iter().map(|row| row[i]).sum::<usize>();
+                let agreed_frames = confusion[i][i];
+                let precision = ratio(agreed_frames, other_frames);
+                let recall = ratio(agreed_frames, reference_frames);
+                LabelAgreement {
+                    label: label.clone(),
+                    reference_frames,
+                    other_frames,
+                    agreed_frames,
+                    precision,
+                    recall,
+                    f1: f1(precision, recall),
+                }
+            })
+            .collect();
+
+        self.frames = frames;
+        self.labels = labels;
+        self.confusion = confusion;
+        self.compute_chance_corrected();
+    }
+
+    /// Computes Cohen's kappa and Krippendorff's alpha from the confusion matrix.
+    #[allow(clippy::cast_precision_loss)]
+    fn compute_chance_corrected(&mut self) {
+        if self.frames == 0 {
+            return;
+        }
+
+        let n = self.frames as f64;
+        let agreed = self
+            .per_label
+            .iter()
+            .map(|label| label.agreed_frames)
+            .sum::<usize>();
+        self.observed_agreement = agreed as f64 / n;
+
+        // Cohen's kappa: chance agreement from each tier's own label distribution.
+        let expected = self
+            .per_label
+            .iter()
+            .map(|label| label.reference_frames as f64 * label.other_frames as f64)
+            .sum::<f64>()
+            / (n * n);
+
+        // Krippendorff's alpha: chance disagreement from the pooled label distribution.
+        let pooled = 2.0 * n;
+        let expected_disagreement = 1.0
+            - self
+                .per_label
+                .iter()
+                .map(|label| {
+                    let count = (label.reference_frames + label.other_frames) as f64;
+                    count * (count - 1.0)
+                })
+                .sum::<f64>()
+                / (pooled * (pooled - 1.0));
+
+        self.cohens_kappa = chance_corrected(self.observed_agreement, expected);
+        self.krippendorffs_alpha = if expected_disagreement > 0.0 {
+            1.0 - (1.0 - self.observed_agreement) / expected_disagreement
+        } else {
+            1.0
+        };
+    }
+}
+
+/// Divides two counts, or returns 1 if there is nothing to count.
+#[allow(clippy::cast_precision_loss)]
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Returns the harmonic mean of a precision and a recall.
+fn f1(precision: f64, recall: f64) -> f64 {
+    if precision + recall > 0.0 {
+        2.0 * precision * recall / (precision + recall)
+    } else {
+        0.0
+    }
+}
+
+/// Corrects an observed agreement for the agreement expected by chance.
+fn chance_corrected(observed: f64, expected: f64) -> f64 {
+    if expected < 1.0 {
+        (observed - expected) / (1.0 - expected)
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_agreement {
+    use crate::interval::{Interval, Tier as IntervalTier};
+
+    fn tier(intervals: &[(f64, f64, &str)]) -> IntervalTier {
+        IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            4.0,
+            intervals
+                .iter()
+                .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn agreement() {
+        let reference = tier(&[(0.0, 1.0, "a"), (1.0, 2.0, "b"), (2.0, 4.0, "a")]);
+        let other = tier(&[(0.0, 1.05, "a"), (1.05, 3.0, "b"), (3.0, 4.0, "a")]);
+        let report = reference.agreement(&other, 0.1, 0.5).unwrap();
+
+        assert_eq!(*report.matched_boundaries(), 1);
+        assert_eq!(*report.boundary_precision(), 0.5);
+        assert_eq!(*report.boundary_recall(), 0.5);
+        assert_eq!(*report.boundary_f1(), 0.5);
+        assert!((report.mean_boundary_displacement().unwrap() - 0.05).abs() < 1e-9);
+
+        assert_eq!(*report.frames(), 8);
+        assert_eq!(*report.observed_agreement(), 0.75);
+        assert_eq!(*report.cohens_kappa(), 0.5);
+        assert_eq!(*report.krippendorffs_alpha(), 0.5);
+        assert_eq!(report.labels(), &vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(report.confusion(), &vec![vec![4, 2], vec![0, 2]]);
+
+        let b = &report.per_label()[1];
+        assert_eq!((*b.reference_frames(), *b.other_frames()), (2, 4));
+        assert_eq!((*b.precision(), *b.recall()), (0.5, 1.0));
+    }
+
+    #[test]
+    fn identical() {
+        let reference = tier(&[(0.0, 1.0, "a"), (1.0, 4.0, "")]);
+        let report = reference.agreement(&reference, 0.0, 0.01).unwrap();
+
+        assert_eq!(*report.boundary_f1(), 1.0);
+        assert_eq!(*report.mean_boundary_displacement(), Some(0.0));
+        assert_eq!(*report.cohens_kappa(), 1.0);
+        assert_eq!(*report.krippendorffs_alpha(), 1.0);
+
+        assert!(reference.agreement(&reference, -1.0, 0.01).is_err());
+        assert!(reference.agreement(&reference, 0.0, 0.0).is_err());
+    }
+}
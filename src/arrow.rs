@@ -0,0 +1,214 @@
+use std::{
+    fs::{self, File},
+    io::{Error, ErrorKind, Result},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use arrow_array::{
+    builder::{Float64Builder, StringDictionaryBuilder},
+    types::{Int32Type, Int8Type},
+    ArrayRef, RecordBatch,
+};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::textgrid::{TextGrid, Tier};
+
+/// Returns the schema of the annotation table built by `textgrids_to_record_batch`.
+///
+/// Every row is one interval or point, with the columns `file`, `tier`, `tier_kind`
+/// (`"interval"` or `"point"`), `xmin`, `xmax` and `label`. Points have equal `xmin` and
+/// `xmax`. The string columns are dictionary-encoded so that repeated tier names and labels
+/// are stored once.
+#[must_use]
+pub fn annotation_schema() -> Schema {
+    let dictionary =
+        |key_type: DataType| DataType::Dictionary(Box::new(key_type), Box::new(DataType::Utf8));
+
+    Schema::new(vec![
+        Field::new("file", dictionary(DataType::Int32), false),
+        Field::new("tier", dictionary(DataType::Int32), false),
+        Field::new("tier_kind", dictionary(DataType::Int8), false),
+        Field::new("xmin", DataType::Float64, false),
+        Field::new("xmax", DataType::Float64, false),
+        Field::new("label", dictionary(DataType::Int32), false),
+    ])
+}
+
+/// Flattens one or more `TextGrid`s into a single Arrow `RecordBatch` following
+/// `annotation_schema`, with the `TextGrid` names in the `file` column.
+///
+/// # Arguments
+///
+/// * `textgrids` - The `TextGrid`s to flatten.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be built, e.g. if a dictionary overflows its key type.
+pub fn textgrids_to_record_batch(textgrids: &[TextGrid]) -> Result<RecordBatch> {
+    let mut files = StringDictionaryBuilder::<Int32Type>::new();
+    let mut tiers = StringDictionaryBuilder::<Int32Type>::new();
+    let mut tier_kinds = StringDictionaryBuilder::<Int8Type>::new();
+    let mut xmins = Float64Builder::new();
+    let mut xmaxs = Float64Builder::new();
+    let mut labels = StringDictionaryBuilder::<Int32Type>::new();
+
+    let mut append =
+        |file: &str, tier: &str, tier_kind: &str, xmin: f64, xmax: f64, label: &str| {
+            files.append(file).map_err(arrow_error)?;
+            tiers.append(tier).map_err(arrow_error)?;
+            tier_kinds.append(tier_kind).map_err(arrow_error)?;
+            xmins.append_value(xmin);
+            xmaxs.append_value(xmax);
+            labels.append(label).map_err(arrow_error)?;
+            Ok::<(), Error>(())
+        };
+
+    for textgrid in textgrids {
+        for tier in textgrid.tiers() {
+            match tier {
+                Tier::IntervalTier(interval_tier) => {
+                    for interval in interval_tier.intervals() {
+                        append(
+                            textgrid.name(),
+                            interval_tier.name(),
+                            "interval",
+                            *interval.xmin(),
+                            *interval.xmax(),
+                            interval.text(),
+                        )?;
+                    }
+                }
+                Tier::PointTier(point_tier) => {
+                    for point in point_tier.points() {
+                        append(
+                            textgrid.name(),
+                            point_tier.name(),
+                            "point",
+                            *point.number(),
+                            *point.number(),
+                            point.mark(),
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(files.finish()),
+        Arc::new(tiers.finish()),
+        Arc::new(tier_kinds.finish()),
+        Arc::new(xmins.finish()),
+        Arc::new(xmaxs.finish()),
+        Arc::new(labels.finish()),
+    ];
+
+    RecordBatch::try_new(Arc::new(annotation_schema()), columns).map_err(arrow_error)
+}
+
+/// Writes one or more `TextGrid`s to a Parquet file as a table following `annotation_schema`.
+///
+/// # Arguments
+///
+/// * `textgrids` - The `TextGrid`s to write.
+/// * `path` - The path to the Parquet file. Missing parent folders are created.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be built or there was a problem writing the file.
+pub fn write_parquet(textgrids: &[TextGrid], path: PathBuf) -> Result<()> {
+    let batch = textgrids_to_record_batch(textgrids)?;
+
+    let mut parent_path = path.clone();
+    if parent_path.pop() {
+        fs::create_dir_all(parent_path)?;
+    }
+
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None).map_err(arrow_error)?;
+    writer.write(&batch).map_err(arrow_error)?;
+    writer.close().map_err(arrow_error)?;
+
+    Ok(())
+}
+
+/// Converts an Arrow or Parquet error into an `std::io::Error`.
+fn arrow_error<E: std::error::Error + Send + Sync + 'static>(error: E) -> Error {
+    Error::new(ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_arrow {
+    use std::fs::File;
+
+    use arrow_array::{cast::AsArray, types::Int32Type, Array, Float64Array};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use crate::{
+        arrow::{textgrids_to_record_batch, write_parquet},
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::{TextGrid, Tier},
+    };
+
+    fn textgrid(name: &str) -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 2.3, Vec::new(), name.to_string());
+        textgrid.push_tier(
+            Tier::IntervalTier(IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                2.3,
+                vec![
+                    Interval::new(0.0, 1.0, "a".to_string()),
+                    Interval::new(1.0, 2.3, "a".to_string()),
+                ],
+            )),
+            false,
+        );
+        textgrid.push_tier(
+            Tier::PointTier(PointTier::new(
+                "tones".to_string(),
+                0.0,
+                2.3,
+                vec![Point::new(1.5, "H*".to_string())],
+            )),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn record_batch() {
+        let batch = textgrids_to_record_batch(&[textgrid("one"), textgrid("two")]).unwrap();
+
+        assert_eq!(batch.num_rows(), 6);
+        assert_eq!(batch.num_columns(), 6);
+
+        let labels = batch.column(5).as_dictionary::<Int32Type>();
+        assert_eq!(labels.values().len(), 2);
+
+        let xmax = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(xmax.value(2), 1.5);
+    }
+
+    #[test]
+    fn parquet() {
+        let path = std::env::temp_dir().join("textgridde-rs-test-arrow/annotations.parquet");
+
+        write_parquet(&[textgrid("one")], path.clone()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows = reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>();
+        assert_eq!(rows, 3);
+    }
+}
@@ -0,0 +1,53 @@
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![deny(clippy::cargo)]
+
+//! A git merge driver for `.TextGrid` files.
+//!
+//! Usage: `textgrid-merge <base> <ours> <theirs>`
+//!
+//! Merges `theirs` into `ours` using `TextGrid::merge_three_way`, writes the result over `ours`
+//! in the long format and exits with status 1 if there were conflicts.
+
+use std::{env, fs::File, path::PathBuf, process::ExitCode};
+
+use textgridde_rs::{
+    parse_textgrid,
+    textgrid::{OutputFormat, TextGrid},
+};
+
+fn main() -> ExitCode {
+    let paths = env::args().skip(1).map(PathBuf::from).collect::<Vec<_>>();
+    let [base, ours, theirs] = paths.as_slice() else {
+        eprintln!("Usage: textgrid-merge <base> <ours> <theirs>");
+        return ExitCode::from(2);
+    };
+
+    let parse = |path: &PathBuf| {
+        parse_textgrid(path.clone(), false)
+            .map_err(|error| eprintln!("Error: could not read {}: {error}", path.display()))
+    };
+    let (Ok(base), Ok(ours_textgrid), Ok(theirs)) = (parse(base), parse(ours), parse(theirs))
+    else {
+        return ExitCode::from(2);
+    };
+
+    let merge = TextGrid::merge_three_way(&base, &ours_textgrid, &theirs);
+    if let Err(error) = File::create(ours)
+        .and_then(|mut file| merge.textgrid().write_to(&mut file, OutputFormat::Long))
+    {
+        eprintln!("Error: could not write {}: {error}", ours.display());
+        return ExitCode::from(2);
+    }
+
+    for conflict in merge.conflicts() {
+        eprintln!("CONFLICT: {conflict}");
+    }
+
+    if merge.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
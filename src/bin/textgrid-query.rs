@@ -0,0 +1,43 @@
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![deny(clippy::cargo)]
+
+//! Runs a query against a `TextGrid` file.
+//!
+//! Usage: `textgrid-query <file> <query>`
+//!
+//! Prints one match per line as tab-separated tier name, index, xmin, xmax and text. See
+//! `textgridde_rs::query::Query` for the query syntax, e.g.
+//! `textgrid-query speech.TextGrid 'phones where label ~ "^[aeiou]$" and prev label ~ "^[mn]$"'`.
+
+use std::{env, path::PathBuf, process::ExitCode};
+
+use textgridde_rs::parse_textgrid;
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let [path, query] = args.as_slice() else {
+        eprintln!("Usage: textgrid-query <file> <query>");
+        return ExitCode::from(2);
+    };
+
+    let matches =
+        parse_textgrid(PathBuf::from(path), false).and_then(|textgrid| textgrid.query(query));
+    match matches {
+        Ok(matches) => {
+            for found in &matches {
+                println!("{found}");
+            }
+            if matches.is_empty() {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
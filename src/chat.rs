@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use regex::Regex;
+
+use crate::{
+    input::Source,
+    point::{Point, Tier as PointTier},
+    textgrid::{TextGrid, Tier},
+    utilities::{contiguous_tier, get_file_content},
+};
+
+/// A main tier line of a CHAT file together with its dependent tiers.
+#[derive(Default)]
+struct Utterance {
+    speaker: String,
+    text: String,
+    bullet: Option<(f64, f64)>,
+    words: Vec<(f64, f64, String)>,
+    events: Vec<String>,
+    comments: Vec<String>,
+}
+
+/// The contents of a CHAT file relevant to a `TextGrid`.
+struct Transcript {
+    speakers: Vec<String>,
+    utterances: Vec<Utterance>,
+    /// `@Comment` lines, each paired with the number of utterances preceding it.
+    loose_comments: Vec<(usize, String)>,
+}
+
+impl Utterance {
+    /// Returns the time span of the utterance, from its main tier bullet or its `%wor` bullets.
+    fn span(&self) -> Option<(f64, f64)> {
+        self.bullet.or_else(|| {
+            let start = self.words.first()?.0;
+            let end = self.words.last()?.1;
+            Some((start, end))
+        })
+    }
+}
+
+/// Parses a CLAN CHAT (`.cha`) file into a `TextGrid`.
+///
+/// Each speaker gets an `IntervalTier` named after their code (e.g. `CHI`), in the order of
+/// the `@Participants` header. Utterances are timed by their media bullets. If an utterance
+/// has a `%wor` tier with word bullets, each word becomes its own interval; otherwise the
+/// whole utterance becomes one interval. `&=` events go on the `events` point tier, and
+/// `%com` and `@Comment` lines go on the `comments` point tier. Untimed utterances are
+/// dropped, along with their events and comments.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or if a bullet's times are out of range.
+pub fn parse_chat<I: Into<Source>>(input: I) -> Result<TextGrid> {
+    let (content, name) = get_file_content(input.into())?;
+
+    let Transcript {
+        speakers,
+        utterances,
+        loose_comments,
+    } = read_utterances(&join_continuation_lines(content))?;
+
+    let xmax = utterances
+        .iter()
+        .filter_map(Utterance::span)
+        .map(|(_, end)| end)
+        .fold(0.0, f64::max);
+
+    let mut spans = HashMap::<&str, Vec<(f64, f64, String)>>::new();
+    let mut events = Vec::<Point>::new();
+    let mut comments = Vec::<Point>::new();
+
+    for (index, utterance) in utterances.iter().enumerate() {
+        let Some((start, end)) = utterance.span() else {
+            continue;
+        };
+
+        let speaker_spans = spans.entry(utterance.speaker.as_str()).or_default();
+        if utterance.words.is_empty() {
+            speaker_spans.push((start, end, utterance.text.clone()));
+        } else {
+            speaker_spans.extend(utterance.words.iter().cloned());
+        }
+
+        for event in &utterance.events {
+            events.push(Point::new(start, event.clone()));
+        }
+        for comment in &utterance.comments {
+            comments.push(Point::new(start, comment.clone()));
+        }
+        for (_, comment) in loose_comments
+            .iter()
+            .filter(|(position, _)| *position == index + 1)
+        {
+            comments.push(Point::new(end, comment.clone()));
+        }
+    }
+    for (_, comment) in loose_comments.iter().filter(|(position, _)| *position == 0) {
+        comments.push(Point::new(0.0, comment.clone()));
+    }
+
+    let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), name);
+
+    for speaker in &speakers {
+        let speaker_spans = spans.remove(speaker.as_str()).unwrap_or_default();
+        textgrid.push_tier(
+            Tier::IntervalTier(contiguous_tier(speaker, xmax, speaker_spans)),
+            false,
+        );
+    }
+
+    let mut events_tier = PointTier::new("events".to_string(), 0.0, xmax, Vec::new());
+    events_tier.push_points(events, false);
+    textgrid.push_tier(Tier::PointTier(events_tier), false);
+
+    let mut comments_tier = PointTier::new("comments".to_string(), 0.0, xmax, Vec::new());
+    comments_tier.push_points(comments, false);
+    textgrid.push_tier(Tier::PointTier(comments_tier), false);
+
+    Ok(textgrid)
+}
+
+/// Joins continuation lines, which start with a tab, onto the line they continue.
+fn join_continuation_lines(content: Vec<String>) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    for line in content {
+        let line = line.trim_end_matches('\r');
+        match lines.last_mut() {
+            Some(last) if line.starts_with('\t') => {
+                last.push(' ');
+                last.push_str(line.trim());
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Reads the speakers, utterances and `@Comment` lines of a CHAT file.
+fn read_utterances(lines: &[String]) -> Result<Transcript> {
+    let bullet_re = Regex::new(r"\x15(\d+)_(\d+)\x15").unwrap(); // Unwrap is safe here
+    let word_re = Regex::new(r"(\S+)\s*\x15(\d+)_(\d+)\x15").unwrap(); // Unwrap is safe here
+    let event_re = Regex::new(r"&=(\S+)").unwrap(); // Unwrap is safe here
+
+    let mut speakers = Vec::<String>::new();
+    let mut utterances = Vec::<Utterance>::new();
+    let mut loose_comments = Vec::<(usize, String)>::new();
+
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        if key == "@Participants" {
+            for participant in value.split(',') {
+                if let Some(code) = participant.split_whitespace().next() {
+                    speakers.push(code.to_string());
+                }
+            }
+        } else if key == "@Comment" {
+            loose_comments.push((utterances.len(), value.to_string()));
+        } else if let Some(speaker) = key.strip_prefix('*') {
+            let bullet = bullet_re
+                .captures(value)
+                .map(|captures| parse_bullet(&captures[1], &captures[2]))
+                .transpose()?;
+            let events = event_re
+                .captures_iter(value)
+                .map(|captures| captures[1].to_string())
+                .collect();
+            let without_bullets = bullet_re.replace_all(value, "");
+            let text = event_re.replace_all(&without_bullets, "");
+
+            if !speakers.iter().any(|known| known == speaker) {
+                speakers.push(speaker.to_string());
+            }
+            utterances.push(Utterance {
+                speaker: speaker.to_string(),
+                text: text.split_whitespace().collect::<Vec<&str>>().join(" "),
+                bullet,
+                events,
+                ..Default::default()
+            });
+        } else if let Some(utterance) = utterances.last_mut() {
+            match key {
+                "%wor" => {
+                    for captures in word_re.captures_iter(value) {
+                        let (start, end) = parse_bullet(&captures[2], &captures[3])?;
+                        utterance.words.push((start, end, captures[1].to_string()));
+                    }
+                }
+                "%com" => utterance.comments.push(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(Transcript {
+        speakers,
+        utterances,
+        loose_comments,
+    })
+}
+
+/// Converts a `start_end` media bullet in milliseconds to seconds.
+fn parse_bullet(start: &str, end: &str) -> Result<(f64, f64)> {
+    let parse = |milliseconds: &str| {
+        milliseconds.parse::<u32>().map(f64::from).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("CHAT malformed; bullet time \"{milliseconds}\" is out of range"),
+            )
+        })
+    };
+
+    Ok((parse(start)? / 1000.0, parse(end)? / 1000.0))
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_chat {
+    use crate::{chat::parse_chat, textgrid::Tier};
+
+    const CHAT: &str = "@UTF8\n@Begin\n@Languages:\teng\n@Participants:\tCHI Target_Child, MOT Mother\n@Comment:\tsession start\n*CHI:\tmore cookie . \u{15}1200_2500\u{15}\n%wor:\tmore \u{15}1200_1700\u{15} cookie \u{15}1700_2500\u{15} .\n%com:\tpoints at cookie\n*MOT:\tyou want &=laughs more\n\tcookies ? \u{15}2600_4000\u{15}\n*CHI:\tyes .\n@End\n";
+
+    #[test]
+    fn parse() {
+        let textgrid = parse_chat(CHAT).unwrap();
+
+        assert_eq!(textgrid.xmax(), &4.0);
+        assert_eq!(textgrid.get_size(), 4);
+
+        let Some(Tier::IntervalTier(child)) = textgrid.get_tier("CHI") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(child.get_size(), 4);
+        assert_eq!(child.intervals()[1].text(), "more");
+        assert_eq!(child.intervals()[2].text(), "cookie");
+        assert_eq!(child.intervals()[2].xmin(), &1.7);
+
+        let Some(Tier::IntervalTier(mother)) = textgrid.get_tier("MOT") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(mother.intervals()[1].text(), "you want more cookies ?");
+        assert_eq!(mother.intervals()[1].xmin(), &2.6);
+
+        let Some(Tier::PointTier(events)) = textgrid.get_tier("events") else {
+            panic!("Expected PointTier");
+        };
+        assert_eq!(events.points()[0].mark(), "laughs");
+        assert_eq!(events.points()[0].number(), &2.6);
+
+        let Some(Tier::PointTier(comments)) = textgrid.get_tier("comments") else {
+            panic!("Expected PointTier");
+        };
+        assert_eq!(comments.get_size(), 2);
+        assert_eq!(comments.points()[0].mark(), "session start");
+        assert_eq!(comments.points()[1].mark(), "points at cookie");
+    }
+
+    #[test]
+    fn numbers_in_text() {
+        let textgrid = parse_chat(
+            "@Begin\n@Participants:\tCHI Target_Child\n*CHI:\tmy room is 12_34 now . \u{15}0_1000\u{15}\n@End\n",
+        )
+        .unwrap();
+
+        let Some(Tier::IntervalTier(child)) = textgrid.get_tier("CHI") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(child.intervals()[0].text(), "my room is 12_34 now .");
+        assert_eq!(child.intervals()[0].xmax(), &1.0);
+    }
+}
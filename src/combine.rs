@@ -0,0 +1,552 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use crate::{
+    interval::{Interval, Tier as IntervalTier},
+    point::Tier as PointTier,
+    textgrid::{TextGrid, Tier},
+    tier::AnnotationTier,
+};
+
+/// How to handle a tier that some of the `TextGrid`s being combined do not have.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TierMismatch {
+    /// Keep the tier, padding interval tiers with an empty interval where it is missing.
+    #[default]
+    Pad,
+    /// Drop the tier, keeping only the tiers every `TextGrid` has.
+    Intersect,
+    /// Return an error naming the tier.
+    Error,
+}
+
+/// How to rename a tier whose name is already taken when merging `TextGrid`s.
+///
+/// A `TextGrid` is labelled by its file stem, e.g. `annotator2` for `annotator2.TextGrid`. If
+/// several `TextGrid`s share a stem, as those parsed from strings do, each is labelled by its
+/// stem and its position among the `TextGrid`s being merged, counting this one as 1.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NameCollision {
+    /// Append the label of the tier's `TextGrid`, e.g. `words_annotator2`.
+    #[default]
+    Suffix,
+    /// Prepend the label of the tier's `TextGrid`, e.g. `annotator2_words`.
+    Prefix,
+    /// Return an error naming the tier.
+    Error,
+}
+
+impl TextGrid {
+    /// Concatenates `TextGrid`s in time, like Praat's "Concatenate". Each `TextGrid` is shifted
+    /// to start where the previous one ends, and tiers are matched by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrids` - The `TextGrid`s to concatenate, in order.
+    /// * `policy` - How to handle tiers missing from some of the `TextGrid`s.
+    ///
+    /// # Returns
+    ///
+    /// A new `TextGrid` named after the first one, starting at the first one's xmin, with the
+    /// tiers in the order they first appear.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `textgrids` is empty, if a tier name is used for both an interval
+    /// tier and a point tier, or if a tier is missing and `policy` is `TierMismatch::Error`.
+    pub fn concatenate(textgrids: &[Self], policy: TierMismatch) -> Result<Self> {
+        let first = textgrids.first().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Cannot concatenate an empty list of TextGrids",
+            )
+        })?;
+
+        let mut tiers = combined_tiers(textgrids, policy)?;
+        let mut cursor = *first.xmin();
+
+        for textgrid in textgrids {
+            let dt = cursor - textgrid.xmin();
+            let end = cursor + (textgrid.xmax() - textgrid.xmin());
+
+            for tier in &mut tiers {
+                let name = tier.name().to_string();
+                match tier {
+                    Tier::IntervalTier(target) => {
+                        append_intervals(target, textgrid.interval_tier(&name), dt, cursor, end);
+                    }
+                    Tier::PointTier(target) => {
+                        if let Some(part) = textgrid.point_tier(&name) {
+                            let mut part = part.clone();
+                            part.shift(dt);
+                            target.edit_points(|points| points.extend(part.points().clone()));
+                        }
+                    }
+                }
+            }
+
+            cursor = end;
+        }
+
+        for tier in &mut tiers {
+            tier.set_xmin(*first.xmin(), None);
+            tier.set_xmax(cursor, None);
+        }
+
+        Ok(Self::new(
+            *first.xmin(),
+            cursor,
+            tiers,
+            first.name().clone(),
+        ))
+    }
+
+    /// Splices another `TextGrid` into this one at time `t`. Everything after `t` is shifted
+    /// later by the duration of `other`, opening a gap that `other` fills. An interval that
+    /// straddles `t` is split in two, and both halves keep its label.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time at which to insert `other`.
+    /// * `other` - The `TextGrid` to insert.
+    /// * `policy` - How to handle tiers missing from either `TextGrid`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `t` is outside the `TextGrid`, or for the reasons `concatenate` would.
+    /// The `TextGrid` is left unchanged on error.
+    pub fn insert_at(&mut self, t: f64, other: &Self, policy: TierMismatch) -> Result<()> {
+        if t < *self.xmin() || t > *self.xmax() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Cannot insert at {t} into `{}`, which spans {} to {}",
+                    self.name(),
+                    self.xmin(),
+                    self.xmax()
+                ),
+            ));
+        }
+
+        // `concatenate` starts at the first part's xmin, so `other` is moved to `t` in case
+        // it comes first.
+        let mut other = other.clone();
+        other.shift(t - other.xmin());
+
+        let mut parts = Vec::new();
+        if t > *self.xmin() {
+            parts.push(self.extract_part(*self.xmin(), t, true)?);
+        }
+        parts.push(other);
+        if t < *self.xmax() {
+            parts.push(self.extract_part(t, *self.xmax(), true)?);
+        }
+
+        let mut spliced = Self::concatenate(&parts, policy)?;
+        spliced.set_name(self.name().clone());
+        *self = spliced;
+
+        Ok(())
+    }
+
+    /// Merges the tiers of other `TextGrid`s over the same audio into this one, such as the
+    /// tiers of several annotators.
+    ///
+    /// The `TextGrid`'s range becomes the union of all the ranges, and the edge intervals of
+    /// every interval tier are extended to cover it.
+    ///
+    /// # Arguments
+    ///
+    /// * `others` - The `TextGrid`s whose tiers to add, in order.
+    /// * `policy` - How to rename a tier whose name is already taken. If the new name is also
+    ///   taken, a number is appended as in `push_tier`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tier name is already taken and `policy` is `NameCollision::Error`.
+    /// The `TextGrid` is left unchanged on error.
+    pub fn merge(&mut self, others: &[Self], policy: NameCollision) -> Result<()> {
+        let mut merged = self.clone();
+
+        let stems = std::iter::once(&*self)
+            .chain(others)
+            .map(|textgrid| {
+                Path::new(textgrid.name())
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<String>>();
+
+        for (position, other) in others.iter().enumerate() {
+            let stem = &stems[position + 1];
+            let label = if stems.iter().filter(|other| *other == stem).count() > 1 {
+                format!("{stem}_{}", position + 2)
+            } else {
+                stem.clone()
+            };
+
+            merged.set_xmin(merged.xmin().min(*other.xmin()), false);
+            merged.set_xmax(merged.xmax().max(*other.xmax()), false);
+
+            for tier in other.tiers() {
+                let mut tier = tier.clone();
+                if merged.get_tier(tier.name()).is_some() {
+                    let name = match policy {
+                        NameCollision::Suffix => format!("{}_{label}", tier.name()),
+                        NameCollision::Prefix => format!("{label}_{}", tier.name()),
+                        NameCollision::Error => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!(
+                                    "Tier `{}` of TextGrid `{}` is already in TextGrid `{}`",
+                                    tier.name(),
+                                    other.name(),
+                                    self.name()
+                                ),
+                            ));
+                        }
+                    };
+                    tier.set_name(name);
+                }
+                merged.push_tier(tier, false);
+            }
+        }
+
+        let (xmin, xmax) = (*merged.xmin(), *merged.xmax());
+        let names = merged
+            .tiers()
+            .iter()
+            .map(|tier| tier.name().to_string())
+            .collect::<Vec<String>>();
+        for name in names {
+            if let Some(tier) = merged.tier_mut(&name) {
+                extend_to(tier, xmin, xmax);
+            }
+        }
+
+        *self = merged;
+
+        Ok(())
+    }
+}
+
+/// Appends the intervals of `part`, shifted by `dt`, to `target`, padding with empty intervals
+/// so that `target` stays contiguous up to `end`.
+fn append_intervals(
+    target: &mut IntervalTier,
+    part: Option<&IntervalTier>,
+    dt: f64,
+    start: f64,
+    end: f64,
+) {
+    let mut part = part.cloned().unwrap_or_default();
+    part.shift(dt);
+    let first = part
+        .intervals()
+        .first()
+        .map_or(end, |interval| *interval.xmin());
+    let last = part
+        .intervals()
+        .last()
+        .map_or(end, |interval| *interval.xmax());
+
+    target.edit_intervals(|intervals| {
+        if first > start {
+            intervals.push(Interval::new(start, first, String::new()));
+        }
+        intervals.extend(part.intervals().iter().cloned());
+        if end > last.max(start) {
+            intervals.push(Interval::new(last.max(start), end, String::new()));
+        }
+    });
+}
+
+/// Extends a tier to span `xmin` to `xmax`, stretching the edge intervals of an interval tier.
+/// An empty interval tier gets a single empty interval.
+fn extend_to(tier: &mut Tier, xmin: f64, xmax: f64) {
+    if let Tier::IntervalTier(interval_tier) = tier {
+        interval_tier.edit_intervals(|intervals| {
+            if intervals.is_empty() {
+                intervals.push(Interval::new(xmin, xmax, String::new()));
+            }
+            if let Some(first) = intervals.first_mut() {
+                first.set_xmin(first.xmin().min(xmin));
+            }
+            if let Some(last) = intervals.last_mut() {
+                last.set_xmax(last.xmax().max(xmax));
+            }
+        });
+    }
+
+    tier.set_xmin(xmin, None);
+    tier.set_xmax(xmax, None);
+}
+
+/// Collects an empty tier for every tier name to keep in the combined `TextGrid`, in the order
+/// the names first appear.
+fn combined_tiers(textgrids: &[TextGrid], policy: TierMismatch) -> Result<Vec<Tier>> {
+    let mut tiers = Vec::<Tier>::new();
+
+    for textgrid in textgrids {
+        for tier in textgrid.tiers() {
+            match tiers.iter().find(|other| other.name() == tier.name()) {
+                Some(other) if other.is_interval() != tier.is_interval() => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Tier `{}` is an interval tier in one TextGrid and a point tier in another",
+                            tier.name()
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                None => tiers.push(match tier {
+                    Tier::IntervalTier(_) => {
+                        IntervalTier::new(tier.name().to_string(), 0.0, 0.0, Vec::new()).into()
+                    }
+                    Tier::PointTier(_) => {
+                        PointTier::new(tier.name().to_string(), 0.0, 0.0, Vec::new()).into()
+                    }
+                }),
+            }
+        }
+    }
+
+    let missing = |tier: &Tier| {
+        textgrids
+            .iter()
+            .find(|textgrid| textgrid.get_tier(tier.name()).is_none())
+    };
+
+    match policy {
+        TierMismatch::Pad => {}
+        TierMismatch::Intersect => tiers.retain(|tier| missing(tier).is_none()),
+        TierMismatch::Error => {
+            if let Some((tier, textgrid)) = tiers
+                .iter()
+                .find_map(|tier| missing(tier).map(|textgrid| (tier, textgrid)))
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Tier `{}` is missing from TextGrid `{}`",
+                        tier.name(),
+                        textgrid.name()
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(tiers)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_combine {
+    use crate::{
+        combine::{NameCollision, TierMismatch},
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+    };
+
+    fn sentence(name: &str, xmax: f64, with_tones: bool) -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), name.to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                xmax,
+                vec![Interval::new(0.0, xmax, name.to_string())],
+            )
+            .into(),
+            false,
+        );
+        if with_tones {
+            textgrid.push_tier(
+                PointTier::new(
+                    "tones".to_string(),
+                    0.0,
+                    xmax,
+                    vec![Point::new(xmax / 2.0, "H*".to_string())],
+                )
+                .into(),
+                false,
+            );
+        }
+        textgrid
+    }
+
+    #[test]
+    fn concatenate() {
+        let textgrids = [sentence("daisy", 1.0, true), sentence("bell", 2.0, false)];
+
+        let session = TextGrid::concatenate(&textgrids, TierMismatch::Pad).unwrap();
+        assert_eq!(*session.xmax(), 3.0);
+        assert_eq!(session.name(), "daisy");
+
+        let words = session.interval_tier("words").unwrap();
+        assert_eq!(words.get_size(), 2);
+        assert_eq!(*words.intervals()[1].xmin(), 1.0);
+        assert_eq!(words.intervals()[1].text(), "bell");
+        assert_eq!(*words.xmax(), 3.0);
+
+        let tones = session.point_tier("tones").unwrap();
+        assert_eq!(tones.get_size(), 1);
+        assert_eq!(*tones.xmax(), 3.0);
+    }
+
+    #[test]
+    fn concatenate_padding() {
+        let mut bell = sentence("bell", 2.0, false);
+        bell.push_tier(
+            IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                2.0,
+                vec![Interval::new(0.5, 1.0, "b".to_string())],
+            )
+            .into(),
+            false,
+        );
+        let textgrids = [sentence("daisy", 1.0, true), bell];
+
+        let session = TextGrid::concatenate(&textgrids, TierMismatch::Pad).unwrap();
+        let phones = session.interval_tier("phones").unwrap();
+        assert_eq!(phones.get_size(), 4);
+        assert_eq!(phones.intervals()[2].text(), "b");
+        assert!(phones.check_overlaps(0.0).is_empty());
+
+        let session = TextGrid::concatenate(&textgrids, TierMismatch::Intersect).unwrap();
+        assert_eq!(session.get_size(), 1);
+
+        assert!(TextGrid::concatenate(&textgrids, TierMismatch::Error).is_err());
+        assert!(TextGrid::concatenate(&[], TierMismatch::Pad).is_err());
+    }
+
+    #[test]
+    fn kind_mismatch() {
+        let mut tones = TextGrid::new(0.0, 1.0, Vec::new(), "tones".to_string());
+        tones.push_tier(
+            IntervalTier::new("tones".to_string(), 0.0, 1.0, Vec::new()).into(),
+            false,
+        );
+
+        assert!(
+            TextGrid::concatenate(&[sentence("daisy", 1.0, true), tones], TierMismatch::Pad)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn insert_at() {
+        let mut textgrid = sentence("daisy", 2.0, true);
+
+        textgrid
+            .insert_at(1.0, &sentence("bell", 0.5, false), TierMismatch::Pad)
+            .unwrap();
+        assert_eq!(*textgrid.xmax(), 2.5);
+        assert_eq!(textgrid.name(), "daisy");
+
+        let words = textgrid.interval_tier("words").unwrap();
+        let texts = words
+            .intervals()
+            .iter()
+            .map(|interval| interval.text().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["daisy", "bell", "daisy"]);
+        assert_eq!(*words.intervals()[2].xmin(), 1.5);
+
+        let tones = textgrid.point_tier("tones").unwrap();
+        assert_eq!(*tones.points()[0].number(), 1.0);
+
+        textgrid
+            .insert_at(0.0, &sentence("give", 1.0, false), TierMismatch::Pad)
+            .unwrap();
+        assert_eq!(*textgrid.xmax(), 3.5);
+
+        assert!(textgrid
+            .insert_at(9.0, &sentence("bell", 1.0, false), TierMismatch::Pad)
+            .is_err());
+
+        let mut textgrid = sentence("daisy", 5.0, true);
+        textgrid.shift(5.0);
+        textgrid
+            .insert_at(5.0, &sentence("bell", 2.0, false), TierMismatch::Pad)
+            .unwrap();
+        assert_eq!((*textgrid.xmin(), *textgrid.xmax()), (5.0, 12.0));
+
+        let words = textgrid.interval_tier("words").unwrap();
+        assert_eq!(words.intervals()[0].text(), "bell");
+        assert_eq!(*words.intervals()[0].xmin(), 5.0);
+        assert_eq!(*words.intervals()[1].xmin(), 7.0);
+    }
+
+    #[test]
+    fn merge() {
+        let mut john = sentence("john", 2.0, true);
+        let kelly = sentence("kelly", 3.0, false);
+        john.merge(std::slice::from_ref(&kelly), NameCollision::Suffix)
+            .unwrap();
+        assert_eq!(john.get_size(), 3);
+        assert_eq!(*john.xmax(), 3.0);
+
+        let words = john.interval_tier("words").unwrap();
+        assert_eq!(*words.xmax(), 3.0);
+        assert_eq!(*words.intervals()[0].xmax(), 3.0);
+        assert!(john.interval_tier("words_kelly").is_some());
+        assert_eq!(*john.point_tier("tones").unwrap().xmax(), 3.0);
+
+        let mut john = sentence("john", 2.0, true);
+        john.merge(std::slice::from_ref(&kelly), NameCollision::Prefix)
+            .unwrap();
+        assert!(john.interval_tier("kelly_words").is_some());
+
+        let mut john = sentence("john", 2.0, true);
+        assert!(john.merge(&[kelly], NameCollision::Error).is_err());
+        assert_eq!(*john.xmax(), 2.0);
+    }
+
+    #[test]
+    fn merge_labels() {
+        let mut john = sentence("john", 2.0, false);
+        john.set_name("annotators/john.TextGrid".to_string());
+        let mut kelly = sentence("kelly", 2.0, false);
+        kelly.set_name("annotators/kelly.TextGrid".to_string());
+        john.merge(&[kelly], NameCollision::Suffix).unwrap();
+        assert!(john.interval_tier("words_kelly").is_some());
+
+        // Named like `TextGrid`s parsed from strings
+        let [mut first, second, third] = ["first", "second", "third"].map(|text| {
+            let mut textgrid = sentence(text, 2.0, false);
+            textgrid.set_name("New TextGrid".to_string());
+            textgrid
+        });
+        first
+            .merge(&[second, third], NameCollision::Prefix)
+            .unwrap();
+
+        let names = first
+            .tiers()
+            .iter()
+            .map(|tier| tier.name().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            names,
+            vec!["words", "New TextGrid_2_words", "New TextGrid_3_words"]
+        );
+        assert_eq!(
+            first
+                .interval_tier("New TextGrid_3_words")
+                .unwrap()
+                .intervals()[0]
+                .text(),
+            "third"
+        );
+    }
+}
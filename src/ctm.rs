@@ -0,0 +1,415 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Error, ErrorKind, Result},
+};
+
+use derive_more::Constructor;
+use getset::Getters;
+
+use crate::{
+    input::Source,
+    interval::Tier as IntervalTier,
+    textgrid::{TextGrid, Tier},
+    utilities::{contiguous_tier_with, get_file_content},
+};
+
+/// A single line of a Kaldi/NIST CTM file: `<utt> <channel> <start> <duration> <label> [<confidence>]`.
+#[derive(Clone, Constructor, Debug, Default, Getters)]
+pub struct CtmEntry {
+    #[getset(get = "pub")]
+    utterance: String,
+    #[getset(get = "pub")]
+    channel: String,
+    #[getset(get = "pub")]
+    start: f64,
+    #[getset(get = "pub")]
+    duration: f64,
+    #[getset(get = "pub")]
+    label: String,
+    #[getset(get = "pub")]
+    confidence: Option<f64>,
+}
+
+/// A single line of a Kaldi `segments` file: `<utt> <recording> <start> <end>`.
+#[derive(Clone, Constructor, Debug, Default, Getters)]
+pub struct Segment {
+    #[getset(get = "pub")]
+    utterance: String,
+    #[getset(get = "pub")]
+    recording: String,
+    #[getset(get = "pub")]
+    start: f64,
+    #[getset(get = "pub")]
+    end: f64,
+}
+
+/// A `TextGrid` built from CTM entries, along with the confidence scores of its intervals.
+#[derive(Clone, Debug, Default, Getters)]
+pub struct CtmTextGrid {
+    #[getset(get = "pub")]
+    textgrid: TextGrid,
+    /// Confidence scores keyed by tier name, parallel to that tier's `intervals()`.
+    /// Gap intervals and entries without a confidence column hold `None`.
+    #[getset(get = "pub")]
+    confidences: HashMap<String, Vec<Option<f64>>>,
+}
+
+/// Parses the lines of a CTM file.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, or if a line does not have five or six
+/// fields, or if its times or confidence cannot be parsed as floats.
+pub fn parse_ctm<I: Into<Source>>(input: I) -> Result<Vec<CtmEntry>> {
+    let (content, _) = get_file_content(input.into())?;
+
+    let mut entries = Vec::<CtmEntry>::new();
+
+    for (line_number, line) in content.iter().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(";;") {
+            continue;
+        }
+
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() != 5 && fields.len() != 6 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "CTM malformed; line {} has {} fields, expected 5 or 6",
+                    line_number + 1,
+                    fields.len()
+                ),
+            ));
+        }
+
+        let confidence = fields
+            .get(5)
+            .map(|field| parse_field(field, "confidence", line_number))
+            .transpose()?;
+
+        entries.push(CtmEntry::new(
+            fields[0].to_string(),
+            fields[1].to_string(),
+            parse_field(fields[2], "start", line_number)?,
+            parse_field(fields[3], "duration", line_number)?,
+            fields[4].to_string(),
+            confidence,
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// Parses the lines of a Kaldi `segments` file.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, or if a line does not have exactly four
+/// fields, or if its times cannot be parsed as floats.
+pub fn parse_segments<I: Into<Source>>(input: I) -> Result<Vec<Segment>> {
+    let (content, _) = get_file_content(input.into())?;
+
+    let mut segments = Vec::<Segment>::new();
+
+    for (line_number, line) in content.iter().enumerate() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.is_empty() {
+            continue;
+        }
+        if fields.len() != 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Segments malformed; line {} has {} fields, expected 4",
+                    line_number + 1,
+                    fields.len()
+                ),
+            ));
+        }
+
+        segments.push(Segment::new(
+            fields[0].to_string(),
+            fields[1].to_string(),
+            parse_field(fields[2], "start", line_number)?,
+            parse_field(fields[3], "end", line_number)?,
+        ));
+    }
+
+    Ok(segments)
+}
+
+/// Builds one `TextGrid` per utterance, or per recording if `segments` is given, with one
+/// interval tier per channel. Gaps between entries are filled with empty intervals.
+///
+/// # Arguments
+///
+/// * `entries` - The CTM entries, e.g. from `parse_ctm`.
+/// * `segments` - If `Some`, utterance times are offset by their segment's start and the
+///   utterances are grouped by recording.
+///
+/// # Returns
+///
+/// The `TextGrid`s, sorted by name, each paired with its confidence scores.
+///
+/// # Errors
+///
+/// Returns an error if `segments` is given but does not contain an utterance found in `entries`.
+pub fn ctm_to_textgrids(
+    entries: &[CtmEntry],
+    segments: Option<&[Segment]>,
+) -> Result<Vec<CtmTextGrid>> {
+    let segment_map = segments.map(|segments| {
+        segments
+            .iter()
+            .map(|segment| (segment.utterance.as_str(), segment))
+            .collect::<HashMap<&str, &Segment>>()
+    });
+
+    // recording -> channel -> (start, end, label, confidence)
+    let mut grouped =
+        BTreeMap::<String, BTreeMap<String, Vec<(f64, f64, String, Option<f64>)>>>::new();
+    // recording -> xmax, taken from the segments where available
+    let mut recording_xmax = HashMap::<String, f64>::new();
+
+    for entry in entries {
+        let (recording, offset) = match &segment_map {
+            Some(segment_map) => {
+                let segment = segment_map.get(entry.utterance.as_str()).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Segments missing utterance `{}` found in CTM",
+                            entry.utterance
+                        ),
+                    )
+                })?;
+                let xmax = recording_xmax
+                    .entry(segment.recording.clone())
+                    .or_insert(segment.end);
+                *xmax = xmax.max(segment.end);
+
+                (segment.recording.clone(), segment.start)
+            }
+            None => (entry.utterance.clone(), 0.0),
+        };
+
+        let start = entry.start + offset;
+        grouped
+            .entry(recording)
+            .or_default()
+            .entry(entry.channel.clone())
+            .or_default()
+            .push((
+                start,
+                start + entry.duration,
+                entry.label.clone(),
+                entry.confidence,
+            ));
+    }
+
+    let mut textgrids = Vec::<CtmTextGrid>::new();
+
+    for (recording, channels) in grouped {
+        let entries_xmax = channels
+            .values()
+            .flatten()
+            .map(|(_, end, _, _)| *end)
+            .fold(0.0, f64::max);
+        let xmax = recording_xmax
+            .get(&recording)
+            .copied()
+            .unwrap_or_default()
+            .max(entries_xmax);
+
+        let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), recording);
+        let mut confidences = HashMap::<String, Vec<Option<f64>>>::new();
+
+        for (channel, channel_entries) in channels {
+            let (tier, tier_confidences) = contiguous_tier_with(&channel, xmax, channel_entries);
+
+            confidences.insert(
+                channel,
+                tier_confidences.into_iter().map(Option::flatten).collect(),
+            );
+            textgrid.push_tier(Tier::IntervalTier(tier), false);
+        }
+
+        textgrids.push(CtmTextGrid {
+            textgrid,
+            confidences,
+        });
+    }
+
+    Ok(textgrids)
+}
+
+/// Converts an interval tier into CTM lines, skipping intervals with empty or whitespace-only text.
+///
+/// # Arguments
+///
+/// * `tier` - The tier to convert.
+/// * `utterance` - The utterance or recording ID to write in the first column.
+/// * `channel` - The channel to write in the second column.
+/// * `confidences` - If `Some`, confidence scores parallel to the tier's intervals.
+///
+/// # Returns
+///
+/// A vector of strings containing one CTM line per non-empty interval.
+#[must_use]
+pub fn tier_to_ctm(
+    tier: &IntervalTier,
+    utterance: &str,
+    channel: &str,
+    confidences: Option<&[Option<f64>]>,
+) -> Vec<String> {
+    tier.intervals()
+        .iter()
+        .enumerate()
+        .filter(|(_, interval)| !interval.text().trim().is_empty())
+        .map(|(index, interval)| {
+            let line = format!(
+                "{utterance} {channel} {} {} {}",
+                interval.xmin(),
+                interval.get_duration(),
+                interval.text().trim()
+            );
+
+            match confidences.and_then(|confidences| confidences.get(index).copied().flatten()) {
+                Some(confidence) => format!("{line} {confidence}"),
+                None => line,
+            }
+        })
+        .collect()
+}
+
+/// Parses a single numeric CTM or segments field.
+fn parse_field(field: &str, field_name: &str, line_number: usize) -> Result<f64> {
+    field.parse::<f64>().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Line {} malformed; could not parse `{field_name}` \"{field}\" as a float",
+                line_number + 1
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_ctm {
+    use crate::{
+        ctm::{ctm_to_textgrids, parse_ctm, parse_segments, tier_to_ctm},
+        interval::{Interval, Tier as IntervalTier},
+        textgrid::Tier,
+    };
+
+    const CTM: &str = "utt1 A 0.10 0.20 daisy 0.93\nutt1 A 0.30 0.25 bell 0.71\nutt1 B 0.00 0.40 hello\nutt2 A 0.00 0.50 answer 1.0\n";
+    const SEGMENTS: &str = "utt1 rec1 1.0 2.0\nutt2 rec1 2.0 3.0\n";
+
+    #[test]
+    fn parse() {
+        let entries = parse_ctm(CTM).unwrap();
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].label(), "bell");
+        assert_eq!(entries[1].confidence(), &Some(0.71));
+        assert_eq!(entries[2].confidence(), &None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_ctm("utt1 A 0.1 daisy").is_err());
+        assert!(parse_ctm("utt1 A zero 0.1 daisy").is_err());
+    }
+
+    #[test]
+    fn per_utterance() {
+        let textgrids = ctm_to_textgrids(&parse_ctm(CTM).unwrap(), None).unwrap();
+
+        assert_eq!(textgrids.len(), 2);
+        assert_eq!(textgrids[0].textgrid().name(), "utt1");
+        assert_eq!(textgrids[0].textgrid().get_size(), 2);
+
+        let Tier::IntervalTier(tier) = &textgrids[0].textgrid().tiers()[0] else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.name(), "A");
+        assert_eq!(tier.get_size(), 3);
+        assert_eq!(tier.intervals()[0].text(), "");
+        assert_eq!(tier.intervals()[1].text(), "daisy");
+        assert_eq!(
+            textgrids[0].confidences()["A"],
+            vec![None, Some(0.93), Some(0.71)]
+        );
+    }
+
+    #[test]
+    fn per_recording() {
+        let entries = parse_ctm(CTM).unwrap();
+        let segments = parse_segments(SEGMENTS).unwrap();
+        let textgrids = ctm_to_textgrids(&entries, Some(&segments)).unwrap();
+
+        assert_eq!(textgrids.len(), 1);
+        assert_eq!(textgrids[0].textgrid().xmax(), &3.0);
+
+        let Tier::IntervalTier(tier) = &textgrids[0].textgrid().tiers()[0] else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.intervals()[1].xmin(), &1.1);
+        assert_eq!(tier.intervals().last().unwrap().text(), "");
+        assert_eq!(tier.intervals()[tier.get_size() - 2].text(), "answer");
+    }
+
+    #[test]
+    fn nested_entries() {
+        let entries = parse_ctm("utt1 A 0.0 2.0 daisy 0.9\nutt1 A 0.5 0.5 bell 0.8").unwrap();
+        let textgrids = ctm_to_textgrids(&entries, None).unwrap();
+
+        let Tier::IntervalTier(tier) = &textgrids[0].textgrid().tiers()[0] else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.get_size(), 1);
+        assert!(tier.check_overlaps(0.0).is_empty());
+        assert_eq!(textgrids[0].confidences()["A"], vec![Some(0.9)]);
+    }
+
+    #[test]
+    fn missing_segment() {
+        let entries = parse_ctm(CTM).unwrap();
+        let segments = parse_segments("utt1 rec1 1.0 2.0").unwrap();
+
+        assert!(ctm_to_textgrids(&entries, Some(&segments)).is_err());
+    }
+
+    #[test]
+    fn write() {
+        let tier = IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            1.0,
+            vec![
+                Interval::new(0.0, 0.5, String::new()),
+                Interval::new(0.5, 1.0, "bell".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            tier_to_ctm(&tier, "utt1", "1", None),
+            vec!["utt1 1 0.5 0.5 bell"]
+        );
+        assert_eq!(
+            tier_to_ctm(&tier, "utt1", "1", Some(&[None, Some(0.5)])),
+            vec!["utt1 1 0.5 0.5 bell 0.5"]
+        );
+    }
+}
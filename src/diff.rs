@@ -0,0 +1,751 @@
+use std::fmt::{self, Display, Formatter};
+#[cfg(feature = "serde")]
+use std::io::{Error, ErrorKind, Result};
+
+use getset::Getters;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    interval::{Interval, Tier as IntervalTier},
+    point::Tier as PointTier,
+    textgrid::{TextGrid, Tier},
+    tier::AnnotationTier,
+    utilities::{group_intervals, interval_boundaries, match_times},
+};
+
+/// A single difference between two versions of a `TextGrid`. Times are those of the new
+/// version, unless the change describes something only the old version has.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "change", rename_all = "snake_case")
+)]
+pub enum Change {
+    /// A tier only the new version has.
+    TierAdded { tier: String },
+    /// A tier only the old version has.
+    TierRemoved { tier: String },
+    /// A tier whose name changed but whose content did not.
+    TierRenamed { from: String, to: String },
+    /// The `(xmin, xmax)` of the `TextGrid`, if `tier` is `None`, or of a tier changed.
+    BoundsChanged {
+        tier: Option<String>,
+        from: (f64, f64),
+        to: (f64, f64),
+    },
+    /// A boundary moved by at most the tolerance.
+    BoundaryMoved {
+        tier: String,
+        from: f64,
+        to: f64,
+        delta: f64,
+    },
+    /// An interval where the old version has none.
+    IntervalAdded {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        text: String,
+    },
+    /// An interval where the new version has none.
+    IntervalRemoved {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        text: String,
+    },
+    /// An old interval split into several, at the new boundaries `at`.
+    IntervalSplit {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        text: String,
+        parts: Vec<String>,
+        at: Vec<f64>,
+    },
+    /// Several old intervals merged into one, removing the old boundaries `at`.
+    IntervalsMerged {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        text: String,
+        parts: Vec<String>,
+        at: Vec<f64>,
+    },
+    /// Several old intervals replaced by several new ones with different boundaries.
+    IntervalsRealigned {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    /// The label of an interval changed.
+    LabelChanged {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        from: String,
+        to: String,
+    },
+    /// A point only the new version has.
+    PointAdded {
+        tier: String,
+        time: f64,
+        mark: String,
+    },
+    /// A point only the old version has.
+    PointRemoved {
+        tier: String,
+        time: f64,
+        mark: String,
+    },
+    /// A point moved by at most the tolerance.
+    PointMoved {
+        tier: String,
+        from: f64,
+        to: f64,
+        delta: f64,
+    },
+    /// The mark of a point changed.
+    MarkChanged {
+        tier: String,
+        time: f64,
+        from: String,
+        to: String,
+    },
+}
+
+impl Change {
+    /// Returns whether an interval or point was added or removed, for display.
+    const fn verb(&self) -> &'static str {
+        match self {
+            Self::IntervalRemoved { .. } | Self::PointRemoved { .. } => "removed",
+            _ => "added",
+        }
+    }
+
+    /// Formats a change to several intervals between two aligned boundaries.
+    fn fmt_region(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::IntervalSplit {
+                tier,
+                xmin,
+                xmax,
+                text,
+                parts,
+                at,
+            } => write!(
+                f,
+                "`{tier}`: interval [{xmin}, {xmax}] \"{text}\" split at {} into {}",
+                join_times(at),
+                join_labels(parts)
+            ),
+            Self::IntervalsMerged {
+                tier,
+                xmin,
+                xmax,
+                text,
+                parts,
+                at,
+            } => write!(
+                f,
+                "`{tier}`: intervals {} merged at {} into [{xmin}, {xmax}] \"{text}\"",
+                join_labels(parts),
+                join_times(at)
+            ),
+            Self::IntervalsRealigned {
+                tier,
+                xmin,
+                xmax,
+                from,
+                to,
+            } => write!(
+                f,
+                "`{tier}`: intervals {} in [{xmin}, {xmax}] realigned as {}",
+                join_labels(from),
+                join_labels(to)
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::TierAdded { tier } => write!(f, "tier `{tier}` added"),
+            Self::TierRemoved { tier } => write!(f, "tier `{tier}` removed"),
+            Self::TierRenamed { from, to } => write!(f, "tier `{from}` renamed to `{to}`"),
+            Self::BoundsChanged { tier, from, to } => {
+                if let Some(tier) = tier {
+                    write!(f, "`{tier}`: bounds ")?;
+                } else {
+                    write!(f, "TextGrid bounds ")?;
+                }
+                write!(
+                    f,
+                    "changed from [{}, {}] to [{}, {}]",
+                    from.0, from.1, to.0, to.1
+                )
+            }
+            Self::BoundaryMoved {
+                tier,
+                from,
+                to,
+                delta,
+            } => write!(
+                f,
+                "`{tier}`: boundary moved from {from} to {to} ({delta:+})"
+            ),
+            Self::IntervalAdded {
+                tier,
+                xmin,
+                xmax,
+                text,
+            }
+            | Self::IntervalRemoved {
+                tier,
+                xmin,
+                xmax,
+                text,
+            } => write!(
+                f,
+                "`{tier}`: interval [{xmin}, {xmax}] \"{text}\" {}",
+                self.verb()
+            ),
+            Self::IntervalSplit { .. }
+            | Self::IntervalsMerged { .. }
+            | Self::IntervalsRealigned { .. } => self.fmt_region(f),
+            Self::LabelChanged {
+                tier,
+                xmin,
+                xmax,
+                from,
+                to,
+            } => write!(
+                f,
+                "`{tier}`: label of [{xmin}, {xmax}] changed from \"{from}\" to \"{to}\""
+            ),
+            Self::PointAdded { tier, time, mark } | Self::PointRemoved { tier, time, mark } => {
+                write!(f, "`{tier}`: point {time} \"{mark}\" {}", self.verb())
+            }
+            Self::PointMoved {
+                tier,
+                from,
+                to,
+                delta,
+            } => write!(f, "`{tier}`: point moved from {from} to {to} ({delta:+})"),
+            Self::MarkChanged {
+                tier,
+                time,
+                from,
+                to,
+            } => write!(
+                f,
+                "`{tier}`: mark at {time} changed from \"{from}\" to \"{to}\""
+            ),
+        }
+    }
+}
+
+/// The differences between two versions of a `TextGrid`, as returned by `TextGrid::diff`.
+#[derive(Clone, Debug, Default, Getters, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextGridDiff {
+    #[getset(get = "pub")]
+    changes: Vec<Change>,
+}
+
+impl TextGridDiff {
+    /// Returns `true` if the two versions are the same.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Serializes the diff as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+}
+
+impl Display for TextGridDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TextGrid {
+    /// Compares this `TextGrid`, the old version, with `other`, the new version.
+    ///
+    /// Tiers are matched by name, or by content if a tier was renamed. Boundaries and points
+    /// that moved by at most `tolerance` seconds are reported as moved; anything further away
+    /// is reported as removed and added. Intervals between two matched boundaries are then
+    /// compared to find changed labels and intervals that were split or merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The new version of the `TextGrid`.
+    /// * `tolerance` - The furthest, in seconds, a boundary or point may move and still be
+    ///   considered the same one.
+    ///
+    /// # Returns
+    ///
+    /// The changes, ordered by tier and then by time.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn diff(&self, other: &Self, tolerance: f64) -> TextGridDiff {
+        let mut changes = Vec::new();
+
+        if self.xmin() != other.xmin() || self.xmax() != other.xmax() {
+            changes.push(Change::BoundsChanged {
+                tier: None,
+                from: (*self.xmin(), *self.xmax()),
+                to: (*other.xmin(), *other.xmax()),
+            });
+        }
+
+        let unmatched_new = other
+            .tiers()
+            .iter()
+            .filter(|tier| self.get_tier(tier.name()).is_none())
+            .collect::<Vec<&Tier>>();
+        let mut renamed = Vec::<&str>::new();
+
+        for old in self.tiers() {
+            if let Some(new) = other.get_tier(old.name()) {
+                diff_tiers(old, new, tolerance, &mut changes);
+            } else if let Some(new) = unmatched_new
+                .iter()
+                .find(|new| !renamed.contains(&new.name()) && same_content(old, new, tolerance))
+            {
+                renamed.push(new.name());
+                changes.push(Change::TierRenamed {
+                    from: old.name().to_string(),
+                    to: new.name().to_string(),
+                });
+            } else {
+                changes.push(Change::TierRemoved {
+                    tier: old.name().to_string(),
+                });
+            }
+        }
+
+        for new in unmatched_new {
+            if !renamed.contains(&new.name()) {
+                changes.push(Change::TierAdded {
+                    tier: new.name().to_string(),
+                });
+            }
+        }
+
+        TextGridDiff { changes }
+    }
+}
+
+/// Compares two tiers with the same name.
+#[allow(clippy::float_cmp)]
+fn diff_tiers(old: &Tier, new: &Tier, tolerance: f64, changes: &mut Vec<Change>) {
+    if old.xmin() != new.xmin() || old.xmax() != new.xmax() {
+        changes.push(Change::BoundsChanged {
+            tier: Some(old.name().to_string()),
+            from: (old.xmin(), old.xmax()),
+            to: (new.xmin(), new.xmax()),
+        });
+    }
+
+    match (old, new) {
+        (Tier::IntervalTier(old), Tier::IntervalTier(new)) => {
+            diff_intervals(old, new, tolerance, changes);
+        }
+        (Tier::PointTier(old), Tier::PointTier(new)) => {
+            diff_points(old, new, tolerance, changes);
+        }
+        _ => {
+            changes.push(Change::TierRemoved {
+                tier: old.name().to_string(),
+            });
+            changes.push(Change::TierAdded {
+                tier: new.name().to_string(),
+            });
+        }
+    }
+}
+
+/// Compares two interval tiers by aligning their boundaries, then comparing the intervals
+/// between each pair of consecutive aligned boundaries.
+#[allow(clippy::float_cmp)]
+fn diff_intervals(
+    old: &IntervalTier,
+    new: &IntervalTier,
+    tolerance: f64,
+    changes: &mut Vec<Change>,
+) {
+    let name = old.name();
+    let old_boundaries = interval_boundaries(old);
+    let new_boundaries = interval_boundaries(new);
+    let matches = match_times(&old_boundaries, &new_boundaries, tolerance);
+
+    for &(i, j) in &matches {
+        let (from, to) = (old_boundaries[i], new_boundaries[j]);
+        if from != to {
+            changes.push(Change::BoundaryMoved {
+                tier: name.clone(),
+                from,
+                to,
+                delta: to - from,
+            });
+        }
+    }
+
+    let old_regions = group_intervals(
+        old,
+        &matches
+            .iter()
+            .map(|&(i, _)| old_boundaries[i])
+            .collect::<Vec<f64>>(),
+    );
+    let new_regions = group_intervals(
+        new,
+        &matches
+            .iter()
+            .map(|&(_, j)| new_boundaries[j])
+            .collect::<Vec<f64>>(),
+    );
+
+    for (old_region, new_region) in old_regions.iter().zip(&new_regions) {
+        diff_region(name, old_region, new_region, changes);
+    }
+}
+
+/// Compares the intervals of two tiers that lie between the same pair of aligned boundaries.
+fn diff_region(name: &str, old: &[&Interval], new: &[&Interval], changes: &mut Vec<Change>) {
+    let labels = |intervals: &[&Interval]| {
+        intervals
+            .iter()
+            .map(|interval| interval.text().clone())
+            .collect::<Vec<String>>()
+    };
+    let inner_boundaries = |intervals: &[&Interval]| {
+        intervals
+            .iter()
+            .skip(1)
+            .map(|interval| *interval.xmin())
+            .collect::<Vec<f64>>()
+    };
+
+    match (old, new) {
+        ([], new) => changes.extend(new.iter().map(|interval| Change::IntervalAdded {
+            tier: name.to_string(),
+            xmin: *interval.xmin(),
+            xmax: *interval.xmax(),
+            text: interval.text().clone(),
+        })),
+        (old, []) => changes.extend(old.iter().map(|interval| Change::IntervalRemoved {
+            tier: name.to_string(),
+            xmin: *interval.xmin(),
+            xmax: *interval.xmax(),
+            text: interval.text().clone(),
+        })),
+        ([old], [new]) => {
+            if old.text() != new.text() {
+                changes.push(Change::LabelChanged {
+                    tier: name.to_string(),
+                    xmin: *new.xmin(),
+                    xmax: *new.xmax(),
+                    from: old.text().clone(),
+                    to: new.text().clone(),
+                });
+            }
+        }
+        ([old], parts) => changes.push(Change::IntervalSplit {
+            tier: name.to_string(),
+            xmin: *old.xmin(),
+            xmax: *old.xmax(),
+            text: old.text().clone(),
+            parts: labels(parts),
+            at: inner_boundaries(parts),
+        }),
+        (parts, [new]) => changes.push(Change::IntervalsMerged {
+            tier: name.to_string(),
+            xmin: *new.xmin(),
+            xmax: *new.xmax(),
+            text: new.text().clone(),
+            parts: labels(parts),
+            at: inner_boundaries(parts),
+        }),
+        (old, new) => changes.push(Change::IntervalsRealigned {
+            tier: name.to_string(),
+            xmin: new.first().map_or(0.0, |interval| *interval.xmin()),
+            xmax: new.last().map_or(0.0, |interval| *interval.xmax()),
+            from: labels(old),
+            to: labels(new),
+        }),
+    }
+}
+
+/// Compares two point tiers by aligning their points.
+#[allow(clippy::float_cmp)]
+fn diff_points(old: &PointTier, new: &PointTier, tolerance: f64, changes: &mut Vec<Change>) {
+    let name = old.name();
+    let old_times = old
+        .points()
+        .iter()
+        .map(|point| *point.number())
+        .collect::<Vec<f64>>();
+    let new_times = new
+        .points()
+        .iter()
+        .map(|point| *point.number())
+        .collect::<Vec<f64>>();
+    let matches = match_times(&old_times, &new_times, tolerance);
+
+    let mut events = Vec::<(f64, Change)>::new();
+    for &(i, j) in &matches {
+        let (from, to) = (&old.points()[i], &new.points()[j]);
+        if from.number() != to.number() {
+            events.push((
+                *to.number(),
+                Change::PointMoved {
+                    tier: name.clone(),
+                    from: *from.number(),
+                    to: *to.number(),
+                    delta: to.number() - from.number(),
+                },
+            ));
+        }
+        if from.mark() != to.mark() {
+            events.push((
+                *to.number(),
+                Change::MarkChanged {
+                    tier: name.clone(),
+                    time: *to.number(),
+                    from: from.mark().clone(),
+                    to: to.mark().clone(),
+                },
+            ));
+        }
+    }
+
+    let mut old_matched = vec![false; old.points().len()];
+    let mut new_matched = vec![false; new.points().len()];
+    for &(i, j) in &matches {
+        old_matched[i] = true;
+        new_matched[j] = true;
+    }
+
+    for (i, point) in old.points().iter().enumerate() {
+        if !old_matched[i] {
+            events.push((
+                *point.number(),
+                Change::PointRemoved {
+                    tier: name.clone(),
+                    time: *point.number(),
+                    mark: point.mark().clone(),
+                },
+            ));
+        }
+    }
+    for (j, point) in new.points().iter().enumerate() {
+        if !new_matched[j] {
+            events.push((
+                *point.number(),
+                Change::PointAdded {
+                    tier: name.clone(),
+                    time: *point.number(),
+                    mark: point.mark().clone(),
+                },
+            ));
+        }
+    }
+
+    events.sort_by(|a, b| a.0.total_cmp(&b.0));
+    changes.extend(events.into_iter().map(|(_, change)| change));
+}
+
+/// Returns `true` if two tiers are of the same kind and have the same annotations, with times
+/// at most `tolerance` apart.
+fn same_content(old: &Tier, new: &Tier, tolerance: f64) -> bool {
+    old.is_interval() == new.is_interval()
+        && old.len() == new.len()
+        && old.annotations().zip(new.annotations()).all(|(a, b)| {
+            a.text() == b.text()
+                && (a.xmin() - b.xmin()).abs() <= tolerance
+                && (a.xmax() - b.xmax()).abs() <= tolerance
+        })
+}
+
+/// Joins times for display, e.g. `1, 1.5`.
+fn join_times(times: &[f64]) -> String {
+    times
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Joins labels for display, e.g. `"daisy" | "bell"`.
+fn join_labels(labels: &[String]) -> String {
+    labels
+        .iter()
+        .map(|label| format!("\"{label}\""))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_diff {
+    use crate::{
+        diff::Change,
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+    };
+
+    fn textgrid(words: &[(f64, f64, &str)], tones: &[(f64, &str)]) -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                3.0,
+                words
+                    .iter()
+                    .map(|(xmin, xmax, text)| Interval::new(*xmin, *xmax, (*text).to_string()))
+                    .collect(),
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                tones
+                    .iter()
+                    .map(|(time, mark)| Point::new(*time, (*mark).to_string()))
+                    .collect(),
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn unchanged() {
+        let old = textgrid(&[(0.0, 3.0, "daisy")], &[(1.0, "H*")]);
+
+        assert!(old.diff(&old.clone(), 0.01).is_empty());
+    }
+
+    #[test]
+    fn intervals() {
+        let old = textgrid(
+            &[(0.0, 1.0, "daisy"), (1.0, 2.0, "bell"), (2.0, 3.0, "give")],
+            &[],
+        );
+        let new = textgrid(
+            &[
+                (0.0, 0.5, "dai"),
+                (0.5, 1.02, "sy"),
+                (1.02, 3.0, "bell give"),
+            ],
+            &[],
+        );
+
+        let diff = old.diff(&new, 0.05);
+        let changes = diff.changes();
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(
+            &changes[0],
+            Change::BoundaryMoved { from, to, .. } if *from == 1.0 && *to == 1.02
+        ));
+        assert!(matches!(
+            &changes[1],
+            Change::IntervalSplit { text, parts, .. } if text == "daisy" && parts.len() == 2
+        ));
+        assert!(matches!(
+            &changes[2],
+            Change::IntervalsMerged { at, .. } if at == &vec![2.0]
+        ));
+
+        let labels = old.diff(
+            &textgrid(
+                &[(0.0, 1.0, "daisy"), (1.0, 2.0, "bells"), (2.0, 3.0, "give")],
+                &[],
+            ),
+            0.05,
+        );
+        assert_eq!(
+            labels.to_string(),
+            "`words`: label of [1, 2] changed from \"bell\" to \"bells\"\n"
+        );
+    }
+
+    #[test]
+    fn points() {
+        let old = textgrid(&[], &[(1.0, "H*"), (2.0, "L%")]);
+        let new = textgrid(&[], &[(1.01, "L*"), (2.5, "L%")]);
+
+        let changes = old.diff(&new, 0.05).changes().clone();
+        assert_eq!(changes.len(), 4);
+        assert!(matches!(&changes[0], Change::PointMoved { .. }));
+        assert!(matches!(&changes[1], Change::MarkChanged { .. }));
+        assert!(matches!(&changes[2], Change::PointRemoved { time, .. } if *time == 2.0));
+        assert!(matches!(&changes[3], Change::PointAdded { time, .. } if *time == 2.5));
+    }
+
+    #[test]
+    fn tiers() {
+        let old = textgrid(&[(0.0, 3.0, "daisy")], &[(1.0, "H*")]);
+        let mut new = old.clone();
+        new.edit_tier("tones", |tier| tier.set_name("accents".to_string()));
+        new.delete_tier("words", false);
+        new.push_tier(
+            IntervalTier::new("phones".to_string(), 0.0, 3.0, vec![]).into(),
+            false,
+        );
+
+        let changes = old.diff(&new, 0.0).changes().clone();
+        assert_eq!(
+            changes,
+            vec![
+                Change::TierRemoved {
+                    tier: "words".to_string()
+                },
+                Change::TierRenamed {
+                    from: "tones".to_string(),
+                    to: "accents".to_string()
+                },
+                Change::TierAdded {
+                    tier: "phones".to_string()
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json() {
+        let old = textgrid(&[(0.0, 3.0, "daisy")], &[]);
+        let new = textgrid(&[(0.0, 3.0, "bell")], &[]);
+
+        let json = old.diff(&new, 0.0).to_json().unwrap();
+        assert!(json.contains("\"change\": \"label_changed\""));
+    }
+}
@@ -0,0 +1,484 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::Source,
+    point::{Point, Tier as PointTier},
+    textgrid::{TextGrid, Tier},
+    utilities::{contiguous_tier, get_file_content},
+};
+
+/// An EMU-SDMS `_annot.json` document.
+#[derive(Clone, Debug, Default, Deserialize, Getters, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotJson {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get = "pub")]
+    annotates: String,
+    #[getset(get = "pub")]
+    sample_rate: f64,
+    #[getset(get = "pub")]
+    levels: Vec<Level>,
+    #[getset(get = "pub")]
+    links: Vec<Link>,
+}
+
+/// The type of an EMU level.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LevelType {
+    /// Items with a start sample and a duration in samples.
+    #[default]
+    Segment,
+    /// Items with a single sample point.
+    Event,
+    /// Items without time information, timed only through their links.
+    Item,
+}
+
+/// A level of an annotJSON document.
+#[derive(Clone, Debug, Default, Deserialize, Getters, Serialize)]
+pub struct Level {
+    #[getset(get = "pub")]
+    name: String,
+    #[serde(rename = "type")]
+    #[getset(get = "pub")]
+    kind: LevelType,
+    #[getset(get = "pub")]
+    items: Vec<Item>,
+}
+
+/// An item of an annotJSON level.
+#[derive(Clone, Debug, Default, Deserialize, Getters, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    #[getset(get = "pub")]
+    id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    sample_start: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    sample_dur: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    sample_point: Option<i64>,
+    #[getset(get = "pub")]
+    labels: Vec<Label>,
+}
+
+/// A label attached to an annotJSON item.
+#[derive(Clone, Debug, Default, Deserialize, Getters, Serialize)]
+pub struct Label {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get = "pub")]
+    value: String,
+}
+
+/// A link between two annotJSON items, from the parent to the child.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Getters, Serialize)]
+pub struct Link {
+    #[serde(rename = "fromID")]
+    #[getset(get = "pub")]
+    from_id: u64,
+    #[serde(rename = "toID")]
+    #[getset(get = "pub")]
+    to_id: u64,
+}
+
+impl AnnotJson {
+    /// Serializes the document as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+
+    /// Returns the label of an item on its own level, falling back to its first label.
+    fn item_label(level: &Level, item: &Item) -> String {
+        item.labels
+            .iter()
+            .find(|label| label.name == level.name)
+            .or_else(|| item.labels.first())
+            .map(|label| label.value.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Parses an EMU-SDMS `_annot.json` file.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or is not a valid annotJSON document.
+pub fn parse_annot_json<I: Into<Source>>(input: I) -> Result<AnnotJson> {
+    let (content, _) = get_file_content(input.into())?;
+
+    serde_json::from_str(&content.join("\n")).map_err(|error| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("annotJSON malformed; {error}"),
+        )
+    })
+}
+
+/// Converts a `TextGrid` to an annotJSON document.
+///
+/// Interval tiers become `SEGMENT` levels and point tiers become `EVENT` levels, with times
+/// converted to samples. Links are inferred by time containment for each `(parent, child)`
+/// pair of tier names in `links`: every child item lying entirely within a parent segment is
+/// linked to it.
+///
+/// # Arguments
+///
+/// * `textgrid` - The `TextGrid` to convert.
+/// * `sample_rate` - The sample rate of the annotated audio.
+/// * `links` - The `(parent, child)` tier name pairs to infer links for.
+#[must_use]
+pub fn textgrid_to_annot_json(
+    textgrid: &TextGrid,
+    sample_rate: f64,
+    links: &[(&str, &str)],
+) -> AnnotJson {
+    let to_samples = |seconds: f64| {
+        #[allow(clippy::cast_possible_truncation)]
+        let samples = (seconds * sample_rate).round() as i64;
+        samples
+    };
+
+    let mut next_id = 1;
+    let mut levels = Vec::<Level>::new();
+
+    for tier in textgrid.tiers() {
+        let level = match tier {
+            Tier::IntervalTier(interval_tier) => Level {
+                name: interval_tier.name().clone(),
+                kind: LevelType::Segment,
+                items: interval_tier
+                    .intervals()
+                    .iter()
+                    .map(|interval| {
+                        let sample_start = to_samples(*interval.xmin());
+                        next_id += 1;
+                        Item {
+                            id: next_id - 1,
+                            sample_start: Some(sample_start),
+                            sample_dur: Some(to_samples(*interval.xmax()) - sample_start - 1),
+                            sample_point: None,
+                            labels: vec![Label {
+                                name: interval_tier.name().clone(),
+                                value: interval.text().clone(),
+                            }],
+                        }
+                    })
+                    .collect(),
+            },
+            Tier::PointTier(point_tier) => Level {
+                name: point_tier.name().clone(),
+                kind: LevelType::Event,
+                items: point_tier
+                    .points()
+                    .iter()
+                    .map(|point| {
+                        next_id += 1;
+                        Item {
+                            id: next_id - 1,
+                            sample_start: None,
+                            sample_dur: None,
+                            sample_point: Some(to_samples(*point.number())),
+                            labels: vec![Label {
+                                name: point_tier.name().clone(),
+                                value: point.mark().clone(),
+                            }],
+                        }
+                    })
+                    .collect(),
+            },
+        };
+        levels.push(level);
+    }
+
+    let mut inferred_links = Vec::<Link>::new();
+    for (parent_name, child_name) in links {
+        let parent = levels.iter().find(|level| level.name == *parent_name);
+        let child = levels.iter().find(|level| level.name == *child_name);
+        let (Some(parent), Some(child)) = (parent, child) else {
+            continue;
+        };
+        if parent.kind != LevelType::Segment {
+            continue;
+        }
+
+        for child_item in &child.items {
+            let Some((child_start, child_end)) = item_span(child_item) else {
+                continue;
+            };
+            let container = parent.items.iter().find(|parent_item| {
+                item_span(parent_item)
+                    .is_some_and(|(start, end)| start <= child_start && child_end <= end)
+            });
+            if let Some(container) = container {
+                inferred_links.push(Link {
+                    from_id: container.id,
+                    to_id: child_item.id,
+                });
+            }
+        }
+    }
+
+    let name = Path::new(textgrid.name())
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    AnnotJson {
+        annotates: format!("{name}.wav"),
+        name,
+        sample_rate,
+        levels,
+        links: inferred_links,
+    }
+}
+
+/// Converts an annotJSON document to a `TextGrid`.
+///
+/// `SEGMENT` levels become interval tiers and `EVENT` levels become point tiers. `ITEM`
+/// levels become interval tiers timed by the span of the timed items they are linked to,
+/// directly or transitively; items that cannot be timed this way are dropped. Gaps in
+/// interval tiers are filled with empty intervals.
+///
+/// # Arguments
+///
+/// * `annot_json` - The document to convert.
+#[must_use]
+pub fn annot_json_to_textgrid(annot_json: &AnnotJson) -> TextGrid {
+    let to_seconds = |sample: i64| {
+        #[allow(clippy::cast_precision_loss)]
+        let sample = sample as f64;
+        sample / annot_json.sample_rate
+    };
+
+    // Resolve sample spans for every item, propagating them up through the links until stable
+    let mut spans = annot_json
+        .levels
+        .iter()
+        .flat_map(|level| level.items.iter())
+        .filter_map(|item| item_span(item).map(|span| (item.id, span)))
+        .collect::<HashMap<u64, (i64, i64)>>();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for link in &annot_json.links {
+            let Some(&(child_start, child_end)) = spans.get(&link.to_id) else {
+                continue;
+            };
+            let span = spans
+                .entry(link.from_id)
+                .or_insert((child_start, child_end));
+            let merged = (span.0.min(child_start), span.1.max(child_end));
+            if merged != *span {
+                *span = merged;
+                changed = true;
+            }
+        }
+    }
+
+    let xmax = spans
+        .values()
+        .map(|(_, end)| to_seconds(*end))
+        .fold(0.0, f64::max);
+
+    let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), annot_json.name.clone());
+
+    for level in &annot_json.levels {
+        let tier = match level.kind {
+            LevelType::Segment | LevelType::Item => {
+                let intervals = level
+                    .items
+                    .iter()
+                    .filter_map(|item| {
+                        let (start, end) = spans.get(&item.id)?;
+                        Some((
+                            to_seconds(*start),
+                            to_seconds(*end),
+                            AnnotJson::item_label(level, item),
+                        ))
+                    })
+                    .collect();
+
+                Tier::IntervalTier(contiguous_tier(&level.name, xmax, intervals))
+            }
+            LevelType::Event => {
+                let points = level
+                    .items
+                    .iter()
+                    .filter_map(|item| {
+                        Some(Point::new(
+                            to_seconds(item.sample_point?),
+                            AnnotJson::item_label(level, item),
+                        ))
+                    })
+                    .collect();
+
+                Tier::PointTier(PointTier::new(level.name.clone(), 0.0, xmax, points))
+            }
+        };
+        textgrid.push_tier(tier, false);
+    }
+
+    textgrid
+}
+
+/// Returns the `[start, end)` span of a timed item in samples.
+const fn item_span(item: &Item) -> Option<(i64, i64)> {
+    match (item.sample_start, item.sample_dur, item.sample_point) {
+        (Some(start), Some(duration), _) => Some((start, start + duration + 1)),
+        (_, _, Some(point)) => Some((point, point)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_emu {
+    use crate::{
+        emu::{annot_json_to_textgrid, parse_annot_json, textgrid_to_annot_json, LevelType},
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::{TextGrid, Tier},
+    };
+
+    fn textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 1.0, Vec::new(), "daisy.TextGrid".to_string());
+        textgrid.push_tier(
+            Tier::IntervalTier(IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                1.0,
+                vec![
+                    Interval::new(0.0, 0.5, "daisy".to_string()),
+                    Interval::new(0.5, 1.0, "bell".to_string()),
+                ],
+            )),
+            false,
+        );
+        textgrid.push_tier(
+            Tier::IntervalTier(IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                1.0,
+                vec![
+                    Interval::new(0.0, 0.25, "d".to_string()),
+                    Interval::new(0.25, 0.5, "eI".to_string()),
+                    Interval::new(0.5, 1.0, "bEl".to_string()),
+                ],
+            )),
+            false,
+        );
+        textgrid.push_tier(
+            Tier::PointTier(PointTier::new(
+                "tones".to_string(),
+                0.0,
+                1.0,
+                vec![Point::new(0.3, "H*".to_string())],
+            )),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn export() {
+        let annot_json = textgrid_to_annot_json(
+            &textgrid(),
+            100.0,
+            &[("words", "phones"), ("words", "tones")],
+        );
+
+        assert_eq!(annot_json.name(), "daisy");
+        assert_eq!(annot_json.annotates(), "daisy.wav");
+        assert_eq!(annot_json.levels().len(), 3);
+        assert_eq!(annot_json.levels()[2].kind(), &LevelType::Event);
+
+        let phone = &annot_json.levels()[1].items()[1];
+        assert_eq!(phone.sample_start(), &Some(25));
+        assert_eq!(phone.sample_dur(), &Some(24));
+
+        assert_eq!(annot_json.links().len(), 4);
+        assert_eq!(annot_json.links()[3].from_id(), &1);
+        assert_eq!(annot_json.links()[3].to_id(), &6);
+    }
+
+    #[test]
+    fn round_trip() {
+        let annot_json = textgrid_to_annot_json(&textgrid(), 100.0, &[]);
+        let parsed = parse_annot_json(annot_json.to_json().unwrap()).unwrap();
+        let textgrid = annot_json_to_textgrid(&parsed);
+
+        assert_eq!(textgrid.get_size(), 3);
+        assert_eq!(textgrid.xmax(), &1.0);
+
+        let Some(Tier::IntervalTier(phones)) = textgrid.get_tier("phones") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(phones.intervals()[1].text(), "eI");
+        assert_eq!(phones.intervals()[1].xmin(), &0.25);
+
+        let Some(Tier::PointTier(tones)) = textgrid.get_tier("tones") else {
+            panic!("Expected PointTier");
+        };
+        assert_eq!(tones.points()[0].number(), &0.3);
+    }
+
+    #[test]
+    fn item_levels() {
+        let annot_json = parse_annot_json(
+            r#"{
+                "name": "bell",
+                "annotates": "bell.wav",
+                "sampleRate": 100,
+                "levels": [
+                    {"name": "Word", "type": "ITEM", "items": [
+                        {"id": 1, "labels": [{"name": "Word", "value": "bell"}]}
+                    ]},
+                    {"name": "Phonetic", "type": "SEGMENT", "items": [
+                        {"id": 2, "sampleStart": 10, "sampleDur": 9, "labels": [{"name": "Phonetic", "value": "b"}]},
+                        {"id": 3, "sampleStart": 20, "sampleDur": 29, "labels": [{"name": "Phonetic", "value": "El"}]}
+                    ]}
+                ],
+                "links": [{"fromID": 1, "toID": 2}, {"fromID": 1, "toID": 3}]
+            }"#,
+        )
+        .unwrap();
+
+        let textgrid = annot_json_to_textgrid(&annot_json);
+
+        let Some(Tier::IntervalTier(words)) = textgrid.get_tier("Word") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(words.get_size(), 2);
+        assert_eq!(words.intervals()[1].text(), "bell");
+        assert_eq!(words.intervals()[1].xmin(), &0.1);
+        assert_eq!(words.intervals()[1].xmax(), &0.5);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse_annot_json("{\"name\": 3}").is_err());
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::{
+    interval::{Interval, Tier as IntervalTier},
+    textgrid::TextGrid,
+};
+
+/// A problem found by `TierHierarchy::validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HierarchyIssue {
+    /// A tier in the hierarchy is missing from the `TextGrid` or is not an interval tier.
+    MissingTier { tier: String },
+    /// The interval `index` of the child tier is not within any interval of its parent tier.
+    Orphan {
+        tier: String,
+        index: usize,
+        parent: String,
+    },
+    /// The interval `index` of the child tier reaches `overshoot` seconds past the interval
+    /// `parent_index` of its parent tier that it belongs to.
+    OutsideParent {
+        tier: String,
+        index: usize,
+        parent: String,
+        parent_index: usize,
+        overshoot: f64,
+    },
+}
+
+impl Display for HierarchyIssue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::MissingTier { tier } => write!(f, "`{tier}` is not an interval tier"),
+            Self::Orphan {
+                tier,
+                index,
+                parent,
+            } => write!(f, "`{tier}` interval {index} has no parent in `{parent}`"),
+            Self::OutsideParent {
+                tier,
+                index,
+                parent,
+                parent_index,
+                overshoot,
+            } => write!(
+                f,
+                "`{tier}` interval {index} reaches {overshoot} seconds outside `{parent}` interval {parent_index}"
+            ),
+        }
+    }
+}
+
+/// Parent-child relationships between the interval tiers of a `TextGrid`, such as words
+/// containing phones, kept alongside the flat list of tiers.
+///
+/// Relationships are between tier names. Each tier has at most one parent, and a parent may
+/// have many children. Within a pair, an interval of the child tier belongs to the interval of
+/// the parent tier its midpoint falls in.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TierHierarchy {
+    /// `(child, parent)` pairs, in the order they were declared.
+    parents: Vec<(String, String)>,
+}
+
+impl TierHierarchy {
+    /// Creates a hierarchy with no relationships.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares `child` to be nested in `parent`, replacing any earlier parent of `child`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relationship would make a tier its own ancestor.
+    pub fn declare(&mut self, parent: &str, child: &str) -> Result<()> {
+        let mut ancestor = Some(parent);
+        while let Some(tier) = ancestor {
+            if tier == child {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("`{child}` cannot be nested in `{parent}`, which is nested in it"),
+                ));
+            }
+            ancestor = self.parent_tier(tier);
+        }
+
+        self.parents.retain(|(tier, _)| tier != child);
+        self.parents.push((child.to_string(), parent.to_string()));
+        Ok(())
+    }
+
+    /// Infers the hierarchy of a `TextGrid`'s interval tiers from how their intervals nest.
+    ///
+    /// A tier can be the parent of another if it has fewer intervals and every interval of the
+    /// other tier lies within one of its intervals, give or take `tolerance` seconds. Of the
+    /// tiers that could be a tier's parent, the one with the most intervals is chosen, so words
+    /// become the parent of phones rather than utterances. Ties go to the earlier tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrid` - The `TextGrid` to infer the hierarchy of.
+    /// * `tolerance` - How far a child interval may reach past its parent interval.
+    #[must_use]
+    pub fn infer(textgrid: &TextGrid, tolerance: f64) -> Self {
+        let tiers = textgrid
+            .tiers()
+            .iter()
+            .filter_map(|tier| tier.as_interval())
+            .collect::<Vec<&IntervalTier>>();
+
+        let mut hierarchy = Self::new();
+        for child in &tiers {
+            let parent = tiers
+                .iter()
+                .filter(|parent| {
+                    parent.intervals().len() < child.intervals().len()
+                        && child.intervals().iter().all(|interval| {
+                            containing_interval(parent, interval).is_some_and(|(_, container)| {
+                                overshoot(interval, container) <= tolerance
+                            })
+                        })
+                })
+                .rev()
+                .max_by_key(|parent| parent.intervals().len());
+
+            if let Some(parent) = parent {
+                hierarchy
+                    .parents
+                    .push((child.name().clone(), parent.name().clone()));
+            }
+        }
+
+        hierarchy
+    }
+
+    /// Returns the name of a tier's parent tier, if it has one.
+    #[must_use]
+    pub fn parent_tier(&self, tier: &str) -> Option<&str> {
+        self.parents
+            .iter()
+            .find(|(child, _)| child == tier)
+            .map(|(_, parent)| parent.as_str())
+    }
+
+    /// Returns the names of a tier's child tiers, in the order they were declared.
+    #[must_use]
+    pub fn child_tiers(&self, tier: &str) -> Vec<&str> {
+        self.parents
+            .iter()
+            .filter(|(_, parent)| parent == tier)
+            .map(|(child, _)| child.as_str())
+            .collect()
+    }
+
+    /// Finds the intervals of every child tier that belong to an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrid` - The `TextGrid` the tiers are in.
+    /// * `tier` - The name of the interval's tier.
+    /// * `index` - The index of the interval on its tier.
+    ///
+    /// # Returns
+    ///
+    /// For each child tier found in the `TextGrid`, its name, the index of the first interval
+    /// that belongs to the interval, and the intervals themselves. Empty if the interval does
+    /// not exist.
+    #[must_use]
+    pub fn children_of<'a>(
+        &self,
+        textgrid: &'a TextGrid,
+        tier: &str,
+        index: usize,
+    ) -> Vec<(&'a str, usize, &'a [Interval])> {
+        let Some(parent) = textgrid
+            .interval_tier(tier)
+            .filter(|parent| index < parent.intervals().len())
+        else {
+            return Vec::new();
+        };
+        let belongs = |interval: &&Interval| {
+            containing_interval(parent, interval).is_some_and(|(i, _)| i == index)
+        };
+
+        self.child_tiers(tier)
+            .into_iter()
+            .filter_map(|name| textgrid.interval_tier(name))
+            .map(|child| {
+                let intervals = child.intervals();
+                let start = intervals
+                    .iter()
+                    .position(|interval| belongs(&interval))
+                    .unwrap_or(intervals.len());
+                let end = start + intervals[start..].iter().take_while(belongs).count();
+                (child.name().as_str(), start, &intervals[start..end])
+            })
+            .collect()
+    }
+
+    /// Finds the interval of the parent tier that an interval belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrid` - The `TextGrid` the tiers are in.
+    /// * `tier` - The name of the interval's tier.
+    /// * `index` - The index of the interval on its tier.
+    ///
+    /// # Returns
+    ///
+    /// The name of the parent tier, the index of the parent interval and the interval itself,
+    /// or None if the tier has no parent or no parent interval contains the interval.
+    #[must_use]
+    pub fn parent_of<'a>(
+        &self,
+        textgrid: &'a TextGrid,
+        tier: &str,
+        index: usize,
+    ) -> Option<(&'a str, usize, &'a Interval)> {
+        let child = textgrid.interval_tier(tier)?.intervals().get(index)?;
+        let parent = textgrid.interval_tier(self.parent_tier(tier)?)?;
+        let (parent_index, interval) = containing_interval(parent, child)?;
+
+        Some((parent.name().as_str(), parent_index, interval))
+    }
+
+    /// Checks that every interval of every child tier lies within the interval of its parent
+    /// tier that it belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrid` - The `TextGrid` to check.
+    /// * `tolerance` - How far a child interval may reach past its parent interval.
+    ///
+    /// # Returns
+    ///
+    /// The issues found, by relationship and then by child interval.
+    #[must_use]
+    pub fn validate(&self, textgrid: &TextGrid, tolerance: f64) -> Vec<HierarchyIssue> {
+        let mut issues = Vec::new();
+
+        for (child_name, parent_name) in &self.parents {
+            let (Some(child), Some(parent)) = (
+                textgrid.interval_tier(child_name),
+                textgrid.interval_tier(parent_name),
+            ) else {
+                for tier in [child_name, parent_name] {
+                    if textgrid.interval_tier(tier).is_none() {
+                        issues.push(HierarchyIssue::MissingTier { tier: tier.clone() });
+                    }
+                }
+                continue;
+            };
+
+            for (index, interval) in child.intervals().iter().enumerate() {
+                match containing_interval(parent, interval) {
+                    None => issues.push(HierarchyIssue::Orphan {
+                        tier: child_name.clone(),
+                        index,
+                        parent: parent_name.clone(),
+                    }),
+                    Some((parent_index, container)) => {
+                        let overshoot = overshoot(interval, container);
+                        if overshoot > tolerance {
+                            issues.push(HierarchyIssue::OutsideParent {
+                                tier: child_name.clone(),
+                                index,
+                                parent: parent_name.clone(),
+                                parent_index,
+                                overshoot,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+/// Finds the interval of `parent` that `child`'s midpoint falls in.
+fn containing_interval<'a>(
+    parent: &'a IntervalTier,
+    child: &Interval,
+) -> Option<(usize, &'a Interval)> {
+    let index = parent.interval_index_at(child.get_midpoint())?;
+    Some((index, &parent.intervals()[index]))
+}
+
+/// Returns how far `child` reaches past either end of `parent`, or 0 if it lies within it.
+fn overshoot(child: &Interval, parent: &Interval) -> f64 {
+    (parent.xmin() - child.xmin())
+        .max(child.xmax() - parent.xmax())
+        .max(0.0)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_hierarchy {
+    use crate::{
+        hierarchy::{HierarchyIssue, TierHierarchy},
+        interval::{Interval, Tier as IntervalTier},
+        textgrid::TextGrid,
+    };
+
+    fn textgrid() -> TextGrid {
+        let tier = |name: &str, intervals: &[(f64, f64, &str)]| {
+            IntervalTier::new(
+                name.to_string(),
+                0.0,
+                2.0,
+                intervals
+                    .iter()
+                    .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                    .collect(),
+            )
+            .into()
+        };
+
+        let mut textgrid = TextGrid::new(0.0, 2.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(tier("utterance", &[(0.0, 2.0, "daisy bell")]), false);
+        textgrid.push_tier(
+            tier(
+                "phones",
+                &[
+                    (0.0, 0.5, "d"),
+                    (0.5, 1.001, "ei"),
+                    (1.001, 1.5, "b"),
+                    (1.5, 2.0, "el"),
+                ],
+            ),
+            false,
+        );
+        textgrid.push_tier(
+            tier("words", &[(0.0, 1.0, "daisy"), (1.0, 2.0, "bell")]),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn infer() {
+        let textgrid = textgrid();
+
+        let hierarchy = TierHierarchy::infer(&textgrid, 0.01);
+        assert_eq!(hierarchy.parent_tier("phones"), Some("words"));
+        assert_eq!(hierarchy.parent_tier("words"), Some("utterance"));
+        assert_eq!(hierarchy.parent_tier("utterance"), None);
+        assert_eq!(hierarchy.child_tiers("words"), vec!["phones"]);
+
+        let hierarchy = TierHierarchy::infer(&textgrid, 0.0);
+        assert_eq!(hierarchy.parent_tier("phones"), Some("utterance"));
+    }
+
+    #[test]
+    fn navigation() {
+        let textgrid = textgrid();
+        let mut hierarchy = TierHierarchy::new();
+        hierarchy.declare("words", "phones").unwrap();
+
+        let children = hierarchy.children_of(&textgrid, "words", 1);
+        assert_eq!(children.len(), 1);
+        let (tier, start, phones) = children[0];
+        assert_eq!((tier, start, phones.len()), ("phones", 2, 2));
+        assert_eq!(phones[0].text(), "b");
+
+        let (tier, index, word) = hierarchy.parent_of(&textgrid, "phones", 1).unwrap();
+        assert_eq!((tier, index, word.text().as_str()), ("words", 0, "daisy"));
+        assert!(hierarchy.parent_of(&textgrid, "words", 0).is_none());
+        assert!(hierarchy.children_of(&textgrid, "words", 2).is_empty());
+    }
+
+    #[test]
+    fn declare_and_validate() {
+        let textgrid = textgrid();
+        let mut hierarchy = TierHierarchy::new();
+        hierarchy.declare("utterance", "words").unwrap();
+        hierarchy.declare("words", "phones").unwrap();
+        assert!(hierarchy.declare("phones", "utterance").is_err());
+
+        assert!(hierarchy.validate(&textgrid, 0.01).is_empty());
+        let issues = hierarchy.validate(&textgrid, 0.0);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            HierarchyIssue::OutsideParent {
+                index: 1,
+                parent_index: 0,
+                overshoot,
+                ..
+            } if (overshoot - 0.001).abs() < 1e-9
+        ));
+
+        hierarchy.declare("missing", "phones").unwrap();
+        assert_eq!(
+            hierarchy.validate(&textgrid, 0.01),
+            vec![HierarchyIssue::MissingTier {
+                tier: "missing".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::cmp::Ordering;
+
+use crate::{
+    textgrid::TextGrid,
+    tier::{Annotation, AnnotationTier},
+};
+
+/// A prebuilt interval tree over the intervals or points of a tier, for answering many overlap
+/// queries against the same tier.
+///
+/// The annotations are kept sorted by their start time, and form an implicit balanced binary
+/// tree in which every node stores the latest end time of its subtree. A query visits only the
+/// subtrees that can contain a match, so it takes O(log n + k) time for k matches.
+#[derive(Clone, Debug)]
+pub struct TierIndex<'a> {
+    annotations: Vec<Annotation<'a>>,
+    max_xmax: Vec<f64>,
+}
+
+impl<'a> TierIndex<'a> {
+    /// Builds the index of a tier in O(n log n) time.
+    ///
+    /// # Arguments
+    ///
+    /// * `tier` - Any interval or point tier.
+    #[must_use]
+    pub fn new<T: AnnotationTier + ?Sized>(tier: &'a T) -> Self {
+        let mut annotations = tier.annotations().collect::<Vec<Annotation<'a>>>();
+        annotations.sort_by(|a, b| a.xmin().partial_cmp(&b.xmin()).unwrap_or(Ordering::Equal));
+
+        let mut index = Self {
+            max_xmax: vec![f64::NEG_INFINITY; annotations.len()],
+            annotations,
+        };
+        index.build(0, index.annotations.len());
+
+        index
+    }
+
+    /// Fills `max_xmax` for the subtree spanning `[lo, hi)` and returns its latest end time.
+    fn build(&mut self, lo: usize, hi: usize) -> f64 {
+        if lo >= hi {
+            return f64::NEG_INFINITY;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let max = self.annotations[mid]
+            .xmax()
+            .max(self.build(lo, mid))
+            .max(self.build(mid + 1, hi));
+        self.max_xmax[mid] = max;
+
+        max
+    }
+
+    /// Returns the number of intervals or points in the index.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Returns `true` if the index has no intervals or points.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Finds the intervals or points intersecting `annotation`, as defined by
+    /// `Annotation::intersects`.
+    ///
+    /// # Returns
+    ///
+    /// The matching annotations, in the order of their index on the tier.
+    #[must_use]
+    pub fn intersecting(&self, annotation: &Annotation) -> Vec<Annotation<'a>> {
+        let mut matches = Vec::new();
+        let mut stack = vec![(0, self.annotations.len())];
+
+        while let Some((lo, hi)) = stack.pop() {
+            if lo >= hi {
+                continue;
+            }
+
+            let mid = lo + (hi - lo) / 2;
+            if self.max_xmax[mid] < annotation.xmin() {
+                continue;
+            }
+            stack.push((lo, mid));
+
+            let candidate = &self.annotations[mid];
+            if candidate.xmin() <= annotation.xmax() {
+                stack.push((mid + 1, hi));
+                if candidate.intersects(annotation) {
+                    matches.push(*candidate);
+                }
+            }
+        }
+
+        matches.sort_by_key(Annotation::index);
+        matches
+    }
+
+    /// Finds the intervals sharing a stretch of time with `[t0, t1)` and the points within it.
+    /// If `t0` equals `t1`, finds what is at that time instead, as with a point.
+    ///
+    /// # Returns
+    ///
+    /// The matching annotations, in the order of their index on the tier.
+    #[must_use]
+    pub fn overlapping(&self, t0: f64, t1: f64) -> Vec<Annotation<'a>> {
+        #[allow(clippy::float_cmp)]
+        let query = if t0 == t1 {
+            Annotation::point(0, t0, "")
+        } else {
+            Annotation::interval(0, t0, t1, "")
+        };
+        self.intersecting(&query)
+    }
+
+    /// Joins two indexed tiers, pairing every annotation of this tier with each annotation of
+    /// `other` that it intersects. Runs in O(n log m + k) time for k pairs.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over the pairs, ordered by the start time of this tier's annotation.
+    #[must_use]
+    pub const fn join<'i>(&'i self, other: &'i Self) -> OverlapJoin<'i, 'a> {
+        OverlapJoin {
+            left: self,
+            right: other,
+            position: 0,
+            current: None,
+            matches: Vec::new(),
+        }
+    }
+}
+
+/// An iterator over the intersecting pairs of two indexed tiers. Created by `TierIndex::join`.
+#[derive(Clone, Debug)]
+pub struct OverlapJoin<'i, 'a> {
+    left: &'i TierIndex<'a>,
+    right: &'i TierIndex<'a>,
+    position: usize,
+    current: Option<Annotation<'a>>,
+    matches: Vec<Annotation<'a>>,
+}
+
+impl<'a> Iterator for OverlapJoin<'_, 'a> {
+    type Item = (Annotation<'a>, Annotation<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let (Some(current), Some(matched)) = (self.current, self.matches.pop()) {
+                return Some((current, matched));
+            }
+
+            let current = *self.left.annotations.get(self.position)?;
+            self.position += 1;
+            self.current = Some(current);
+            self.matches = self.right.intersecting(&current);
+            self.matches.reverse();
+        }
+    }
+}
+
+/// The `TierIndex` of every tier in a `TextGrid`, for joins between any two of them.
+#[derive(Clone, Debug)]
+pub struct TextGridIndex<'a> {
+    tiers: Vec<(&'a str, TierIndex<'a>)>,
+}
+
+impl<'a> TextGridIndex<'a> {
+    /// Builds the index of every tier in a `TextGrid`.
+    #[must_use]
+    pub fn new(textgrid: &'a TextGrid) -> Self {
+        Self {
+            tiers: textgrid
+                .tiers()
+                .iter()
+                .map(|tier| (tier.name(), TierIndex::new(tier)))
+                .collect(),
+        }
+    }
+
+    /// Gets the index of a tier using it's name.
+    ///
+    /// # Returns
+    ///
+    /// Returns the index if the tier exists, otherwise None.
+    #[must_use]
+    pub fn tier(&self, name: &str) -> Option<&TierIndex<'a>> {
+        self.tiers
+            .iter()
+            .find(|(tier_name, _)| *tier_name == name)
+            .map(|(_, index)| index)
+    }
+
+    /// Joins two tiers of the `TextGrid`, as in `TierIndex::join`.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over the intersecting pairs, or None if either tier does not exist.
+    #[must_use]
+    pub fn join(&self, left: &str, right: &str) -> Option<OverlapJoin<'_, 'a>> {
+        Some(self.tier(left)?.join(self.tier(right)?))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_index {
+    use crate::{
+        index::{TextGridIndex, TierIndex},
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+        tier::AnnotationTier,
+    };
+
+    fn textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(1.0, 3.0, "bell".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 0.5, "d".to_string()),
+                    Interval::new(0.5, 1.0, "ei".to_string()),
+                    Interval::new(1.0, 2.0, "b".to_string()),
+                    Interval::new(2.0, 3.0, "el".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Point::new(1.0, "H*".to_string()),
+                    Point::new(2.5, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn join() {
+        let textgrid = textgrid();
+        let index = TextGridIndex::new(&textgrid);
+
+        let pairs = index
+            .join("words", "phones")
+            .unwrap()
+            .map(|(word, phone)| (word.text(), phone.text()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                ("daisy", "d"),
+                ("daisy", "ei"),
+                ("bell", "b"),
+                ("bell", "el")
+            ]
+        );
+
+        let pairs = index
+            .join("tones", "words")
+            .unwrap()
+            .map(|(tone, word)| (tone.text(), word.text()))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![("H*", "bell"), ("L%", "bell")]);
+
+        assert!(index.join("words", "missing").is_none());
+    }
+
+    #[test]
+    fn overlapping() {
+        let textgrid = textgrid();
+        let index = TierIndex::new(textgrid.get_tier("phones").unwrap());
+
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.overlapping(0.4, 1.0).len(), 2);
+        assert_eq!(index.overlapping(1.0, 1.0)[0].text(), "b");
+        assert!(index.overlapping(3.0, 4.0).is_empty());
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        let intervals = (0..500_u32)
+            .map(|i| {
+                let xmin = f64::from(i * 7 % 500);
+                Interval::new(xmin, xmin + f64::from(i % 13 + 1), i.to_string())
+            })
+            .collect();
+        let tier = IntervalTier::new("test".to_string(), 0.0, 520.0, intervals);
+        let index = TierIndex::new(&tier);
+
+        for t in 0..60_u32 {
+            let (t0, t1) = (f64::from(t * 9), f64::from(t * 9 + 4));
+            let expected = tier
+                .annotations()
+                .filter(|annotation| annotation.overlaps(t0, t1))
+                .map(|annotation| annotation.index())
+                .collect::<Vec<_>>();
+            let found = index
+                .overlapping(t0, t1)
+                .iter()
+                .map(crate::tier::Annotation::index)
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+    }
+}
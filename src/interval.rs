@@ -1,11 +1,17 @@
 use std::{
     cmp::Ordering,
     fmt::{self, Display, Formatter},
+    io::{Error, ErrorKind, Result},
 };
 
 use derive_more::Constructor;
 use getset::{Getters, Setters};
 
+use crate::{
+    tier::{Annotation, AnnotationTier, TierIssue},
+    utilities::check_scale_factor,
+};
+
 /// An "interval," used in Praat as a specific period of time with an associated label.
 #[derive(Clone, Constructor, Debug, Default, Getters, Setters)]
 pub struct Interval {
@@ -26,8 +32,8 @@ impl Interval {
 
     /// Returns the midpoint of the interval.
     #[must_use]
-    pub fn get_midpoint(&self) -> f64 {
-        (self.xmin + self.xmax) / 2.0
+    pub const fn get_midpoint(&self) -> f64 {
+        f64::midpoint(self.xmin, self.xmax)
     }
 
     /// Sets the xmin value of the interval.
@@ -35,7 +41,7 @@ impl Interval {
     /// # Arguments
     ///
     /// * `xmin` - The xmin value to set.
-    pub fn set_xmin(&mut self, xmin: f64) {
+    pub const fn set_xmin(&mut self, xmin: f64) {
         self.xmin = xmin;
     }
 
@@ -44,7 +50,7 @@ impl Interval {
     /// # Arguments
     ///
     /// * `xmax` - The xmax value to set.
-    pub fn set_xmax(&mut self, xmax: f64) {
+    pub const fn set_xmax(&mut self, xmax: f64) {
         self.xmax = xmax;
     }
 }
@@ -59,6 +65,16 @@ impl Display for Interval {
     }
 }
 
+/// The side of a new boundary that keeps the label of the interval it splits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LabelSide {
+    /// The interval before the boundary keeps the label, as in Praat.
+    #[default]
+    Left,
+    /// The interval after the boundary keeps the label.
+    Right,
+}
+
 /// Represents an interval tier in a `TextGrid`.
 #[derive(Clone, Constructor, Debug, Default, Getters, Setters)]
 pub struct Tier {
@@ -213,38 +229,117 @@ impl Tier {
         self.intervals = intervals;
     }
 
+    /// Edits the intervals of the interval tier in place.
+    /// Afterwards, calls `reorder()` and widens the tier's bounds to cover every interval, so the
+    /// tier stays sorted and within bounds whatever `edit` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - A closure given mutable access to the intervals.
+    ///
+    /// # Returns
+    ///
+    /// The value returned by `edit`.
+    pub fn edit_intervals<R, F: FnOnce(&mut Vec<Interval>) -> R>(&mut self, edit: F) -> R {
+        let result = edit(&mut self.intervals);
+
+        self.reorder();
+        for interval in &self.intervals {
+            self.xmin = self.xmin.min(interval.xmin);
+            self.xmax = self.xmax.max(interval.xmax);
+        }
+
+        result
+    }
+
     /// Sorts the intervals in the interval tier by their minimum x value.
     fn reorder(&mut self) {
         self.intervals
             .sort_by(|a, b| a.xmin.partial_cmp(&b.xmin).unwrap_or(Ordering::Equal));
     }
 
-    /// Checks for overlaps in the interval tier.
-    /// Calls `reorder` to ensure the intervals are sorted by their minimum x value before checking for overlaps.
+    /// Checks the interval tier for gaps, overlaps, intervals outside the tier's bounds and
+    /// intervals with no duration, in linear time. An interval that starts before the previous
+    /// one is reported as unsorted instead of being checked for gaps and overlaps.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - Gaps, overlaps, overshoots and backward steps of at most `epsilon` seconds
+    ///   are ignored, and intervals lasting at most `epsilon` seconds count as having no duration.
     ///
     /// # Returns
     ///
-    /// A vector of pairs of the overlapping intervals' indices or `None` if there are no overlaps.
+    /// The issues found, in order of time.
     #[must_use]
-    pub fn check_overlaps(&self) -> Option<Vec<(u64, u64)>> {
-        let mut overlaps: Vec<(u64, u64)> = Vec::new();
+    pub fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue> {
+        let mut issues = Vec::new();
+        // The interval reaching furthest so far, and where it ends.
+        let mut reach: Option<(usize, f64)> = None;
+
+        for (index, interval) in self.intervals.iter().enumerate() {
+            let end = reach.map_or(self.xmin, |(_, end)| end);
+            let unsorted = index
+                .checked_sub(1)
+                .map(|previous| &self.intervals[previous])
+                .filter(|previous| interval.xmin < previous.xmin - epsilon);
+            if let Some(previous) = unsorted {
+                issues.push(TierIssue::Unsorted {
+                    index,
+                    xmin: interval.xmin,
+                    xmax: previous.xmin,
+                });
+            } else if interval.xmin > end + epsilon {
+                issues.push(TierIssue::Gap {
+                    before: reach.map(|(before, _)| before),
+                    after: Some(index),
+                    xmin: end,
+                    xmax: interval.xmin,
+                });
+            } else if let Some((first, end)) =
+                reach.filter(|(_, end)| interval.xmin < end - epsilon)
+            {
+                issues.push(TierIssue::Overlap {
+                    first,
+                    second: index,
+                    xmin: interval.xmin,
+                    xmax: end.min(interval.xmax),
+                });
+            }
 
-        // iterate over each pair of intervals, checking to make sure the xmax of the first interval is perfectly equal to the xmin of the second interval
-        for (i, window) in self.intervals.windows(2).enumerate() {
-            let interval = &window[0];
-            let next_interval = &window[1];
+            let overshoot = (self.xmin - interval.xmin).max(interval.xmax - self.xmax);
+            if overshoot > epsilon {
+                issues.push(TierIssue::OutOfTierBounds {
+                    index,
+                    xmin: interval.xmin,
+                    xmax: interval.xmax,
+                    overshoot,
+                });
+            }
+            if interval.xmax - interval.xmin <= epsilon {
+                issues.push(TierIssue::ZeroOrNegativeDuration {
+                    index,
+                    xmin: interval.xmin,
+                    xmax: interval.xmax,
+                });
+            }
 
-            #[allow(clippy::float_cmp)]
-            if interval.xmax != next_interval.xmin {
-                overlaps.push((i as u64, (i + 1) as u64));
+            // An unsorted interval would hide the gaps and overlaps between the sorted ones.
+            if unsorted.is_none() && reach.is_none_or(|(_, end)| interval.xmax > end) {
+                reach = Some((index, interval.xmax));
             }
         }
 
-        if overlaps.is_empty() {
-            None
-        } else {
-            Some(overlaps)
+        let end = reach.map_or(self.xmin, |(_, end)| end);
+        if self.xmax > end + epsilon {
+            issues.push(TierIssue::Gap {
+                before: reach.map(|(before, _)| before),
+                after: None,
+                xmin: end,
+                xmax: self.xmax,
+            });
         }
+
+        issues
     }
 
     /// Fixes gaps/overlaps in the interval tier.
@@ -335,6 +430,321 @@ impl Tier {
             }
         }
     }
+
+    /// Inserts a boundary at `t`, splitting the interval that contains it in two, like
+    /// Praat's "Add boundary".
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time of the new boundary.
+    /// * `keep` - Which of the two new intervals keeps the label; the other is left empty.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new boundary, as used by `remove_boundary` and `move_boundary`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `t` is not strictly inside an interval, e.g. if it lies on an
+    /// existing boundary, in a gap or outside the tier.
+    pub fn insert_boundary(&mut self, t: f64, keep: LabelSide) -> Result<usize> {
+        if self.intervals.is_empty() && self.xmin < t && t < self.xmax {
+            self.intervals
+                .push(Interval::new(self.xmin, self.xmax, String::new()));
+        }
+
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.xmax <= t);
+        let Some(interval) = self
+            .intervals
+            .get_mut(index)
+            .filter(|interval| interval.xmin < t)
+        else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Tier `{}` has no interval strictly containing {t}",
+                    self.name
+                ),
+            ));
+        };
+
+        let text = std::mem::take(&mut interval.text);
+        let (left_text, right_text) = match keep {
+            LabelSide::Left => (text, String::new()),
+            LabelSide::Right => (String::new(), text),
+        };
+        let right = Interval::new(t, interval.xmax, right_text);
+        interval.xmax = t;
+        interval.text = left_text;
+        self.intervals.insert(index + 1, right);
+
+        Ok(index)
+    }
+
+    /// Removes the boundary between the intervals at `index` and `index + 1`, merging them into
+    /// one, like Praat's "Remove boundary".
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the boundary, i.e. of the interval it ends.
+    /// * `separator` - The text put between the two labels. Empty labels are skipped, so
+    ///   merging with an empty interval keeps the other label as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no boundary at `index`.
+    pub fn remove_boundary(&mut self, index: usize, separator: &str) -> Result<()> {
+        self.check_boundary(index)?;
+
+        let right = self.intervals.remove(index + 1);
+        let left = &mut self.intervals[index];
+        left.xmax = right.xmax;
+        if left.text.is_empty() {
+            left.text = right.text;
+        } else if !right.text.is_empty() {
+            left.text = format!("{}{separator}{}", left.text, right.text);
+        }
+
+        Ok(())
+    }
+
+    /// Moves the boundary between the intervals at `index` and `index + 1` to `t`, adjusting
+    /// both neighbours, like dragging a boundary in Praat.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the boundary, i.e. of the interval it ends.
+    /// * `t` - The new time of the boundary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no boundary at `index`, or if `t` would reach or cross the
+    /// neighbouring boundaries.
+    pub fn move_boundary(&mut self, index: usize, t: f64) -> Result<()> {
+        self.check_boundary(index)?;
+
+        let lower = self.intervals[index].xmin;
+        let upper = self.intervals[index + 1].xmax;
+        if !(lower < t && t < upper) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Tier `{}` cannot move boundary {index} to {t}; it must stay between {lower} and {upper}",
+                    self.name
+                ),
+            ));
+        }
+
+        self.intervals[index].xmax = t;
+        self.intervals[index + 1].xmin = t;
+
+        Ok(())
+    }
+
+    /// Finds the index of the interval at time `t`.
+    /// Follows Praat's convention: a time on a boundary belongs to the interval that starts
+    /// there, except at the end of the last interval, which belongs to that interval.
+    /// Assumes the intervals do not overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time to look up.
+    ///
+    /// # Returns
+    ///
+    /// The index of the interval or `None` if `t` falls outside every interval.
+    #[must_use]
+    pub fn interval_index_at(&self, t: f64) -> Option<usize> {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.xmin <= t)
+            .checked_sub(1)?;
+        let interval = &self.intervals[index];
+
+        #[allow(clippy::float_cmp)]
+        let at_end = t == interval.xmax && index + 1 == self.intervals.len();
+        (t < interval.xmax || at_end).then_some(index)
+    }
+
+    /// Finds the interval at time `t`, as in `interval_index_at`.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time to look up.
+    ///
+    /// # Returns
+    ///
+    /// The interval or `None` if `t` falls outside every interval.
+    #[must_use]
+    pub fn interval_at(&self, t: f64) -> Option<&Interval> {
+        self.interval_index_at(t)
+            .map(|index| &self.intervals[index])
+    }
+
+    /// Finds the intervals sharing a stretch of time with `[t0, t1)`. Intervals that only touch
+    /// the range at one end are not included. Assumes the intervals do not overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `t0` - The start of the range.
+    /// * `t1` - The end of the range.
+    ///
+    /// # Returns
+    ///
+    /// The overlapping intervals, in order, with the index of the first one.
+    #[must_use]
+    pub fn intervals_overlapping(&self, t0: f64, t1: f64) -> (usize, &[Interval]) {
+        let start = self
+            .intervals
+            .partition_point(|interval| interval.xmax <= t0);
+        let end = self
+            .intervals
+            .partition_point(|interval| interval.xmin < t1)
+            .max(start);
+
+        (start, &self.intervals[start..end])
+    }
+
+    /// Checks that `index` is the index of a boundary between two intervals.
+    fn check_boundary(&self, index: usize) -> Result<()> {
+        if index + 1 < self.intervals.len() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Tier `{}` has no boundary {index}; it has {} intervals",
+                    self.name,
+                    self.intervals.len()
+                ),
+            ))
+        }
+    }
+
+    /// Shifts every time in the interval tier, including its bounds, by `dt` seconds, like
+    /// Praat's "Shift times by".
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The number of seconds to shift by. Negative values shift towards zero.
+    pub fn shift(&mut self, dt: f64) {
+        self.map_times(|t| t + dt);
+    }
+
+    /// Scales every time in the interval tier, including its bounds, by `factor` around `anchor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The scaling factor.
+    /// * `anchor` - The time that stays in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `factor` is not positive and finite.
+    pub fn scale(&mut self, factor: f64, anchor: f64) -> Result<()> {
+        check_scale_factor(factor)?;
+        self.map_times(|t| (t - anchor).mul_add(factor, anchor));
+
+        Ok(())
+    }
+
+    /// Extracts the part of the interval tier between `t0` and `t1`, like Praat's "Extract part".
+    /// Intervals straddling `t0` or `t1` are cut at them, and intervals outside are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `t0` - The start of the part.
+    /// * `t1` - The end of the part.
+    /// * `preserve_times` - If `true`, keeps the original times. If `false`, shifts the part so
+    ///   that it starts at zero.
+    ///
+    /// # Returns
+    ///
+    /// A new interval tier spanning `t0` to `t1`, or `0.0` to `t1 - t0`.
+    #[must_use]
+    pub fn extract_part(&self, t0: f64, t1: f64, preserve_times: bool) -> Self {
+        let intervals = self
+            .intervals_overlapping(t0, t1)
+            .1
+            .iter()
+            .map(|interval| {
+                Interval::new(
+                    interval.xmin.max(t0),
+                    interval.xmax.min(t1),
+                    interval.text.clone(),
+                )
+            })
+            .collect();
+
+        let mut part = Self::new(self.name.clone(), t0, t1, intervals);
+        if !preserve_times {
+            part.shift(-t0);
+        }
+
+        part
+    }
+
+    /// Applies `map` to every time in the interval tier, including its bounds.
+    fn map_times<F: Fn(f64) -> f64>(&mut self, map: F) {
+        self.xmin = map(self.xmin);
+        self.xmax = map(self.xmax);
+        for interval in &mut self.intervals {
+            interval.xmin = map(interval.xmin);
+            interval.xmax = map(interval.xmax);
+        }
+    }
+}
+
+impl AnnotationTier for Tier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn xmin(&self) -> f64 {
+        self.xmin
+    }
+
+    fn xmax(&self) -> f64 {
+        self.xmax
+    }
+
+    fn set_xmin(&mut self, xmin: f64, warn: Option<bool>) {
+        Self::set_xmin(self, xmin, warn);
+    }
+
+    fn set_xmax(&mut self, xmax: f64, warn: Option<bool>) {
+        Self::set_xmax(self, xmax, warn);
+    }
+
+    fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    fn annotations(&self) -> Box<dyn Iterator<Item = Annotation<'_>> + '_> {
+        Box::new(self.intervals.iter().enumerate().map(|(index, interval)| {
+            Annotation::interval(index, interval.xmin, interval.xmax, &interval.text)
+        }))
+    }
+
+    fn annotations_in(&self, t0: f64, t1: f64) -> Vec<Annotation<'_>> {
+        let (start, intervals) = self.intervals_overlapping(t0, t1);
+        (start..)
+            .zip(intervals)
+            .map(|(index, interval)| {
+                Annotation::interval(index, interval.xmin, interval.xmax, &interval.text)
+            })
+            .collect()
+    }
+
+    fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue> {
+        Self::check_overlaps(self, epsilon)
+    }
 }
 
 impl Display for Tier {
@@ -489,10 +899,151 @@ mod test_tier {
         assert_eq!(tier.intervals[1].xmin, 1.0);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn edit_intervals() {
+        let mut tier = Tier::new(
+            "test".to_string(),
+            0.0,
+            2.0,
+            vec![
+                Interval::new(0.0, 1.0, "daisy".to_string()),
+                Interval::new(1.0, 2.0, "bell".to_string()),
+            ],
+        );
+
+        let count = tier.edit_intervals(|intervals| {
+            intervals[0].set_text("daisy daisy".to_string());
+            intervals.push(Interval::new(2.0, 3.0, "give".to_string()));
+            intervals.swap(0, 2);
+            intervals.len()
+        });
+
+        assert_eq!(count, 3);
+        assert_eq!(tier.intervals[0].text, "daisy daisy");
+        assert_eq!(tier.intervals[2].text, "give");
+        assert_eq!(tier.xmax, 3.0);
+    }
+
+    mod boundaries {
+        use crate::interval::{Interval, LabelSide, Tier};
+
+        fn tier() -> Tier {
+            Tier::new(
+                "test".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(1.0, 3.0, "bell".to_string()),
+                ],
+            )
+        }
+
+        #[test]
+        fn insert() {
+            let mut tier = tier();
+
+            assert_eq!(tier.insert_boundary(2.0, LabelSide::Right).unwrap(), 1);
+            assert_eq!(tier.intervals[1].text, "");
+            assert_eq!(tier.intervals[2].text, "bell");
+            assert_eq!(tier.intervals[2].xmin, 2.0);
+
+            assert_eq!(tier.insert_boundary(0.5, LabelSide::Left).unwrap(), 0);
+            assert_eq!(tier.intervals[0].text, "daisy");
+            assert!(tier.check_overlaps(0.0).is_empty());
+
+            assert!(tier.insert_boundary(1.0, LabelSide::Left).is_err());
+            assert!(tier.insert_boundary(4.0, LabelSide::Left).is_err());
+        }
+
+        #[test]
+        fn insert_into_empty() {
+            let mut tier = Tier::new("test".to_string(), 0.0, 3.0, Vec::new());
+
+            assert_eq!(tier.insert_boundary(1.0, LabelSide::Left).unwrap(), 0);
+            assert_eq!(tier.get_size(), 2);
+        }
+
+        #[test]
+        fn remove() {
+            let mut tier = tier();
+            tier.insert_boundary(2.0, LabelSide::Left).unwrap();
+
+            tier.remove_boundary(1, " ").unwrap();
+            assert_eq!(tier.intervals[1].text, "bell");
+
+            tier.remove_boundary(0, " ").unwrap();
+            assert_eq!(tier.get_size(), 1);
+            assert_eq!(tier.intervals[0].text, "daisy bell");
+            assert_eq!(tier.intervals[0].xmax, 3.0);
+
+            assert!(tier.remove_boundary(0, " ").is_err());
+        }
+
+        #[test]
+        fn move_boundary() {
+            let mut tier = tier();
+
+            tier.move_boundary(0, 1.5).unwrap();
+            assert_eq!(tier.intervals[0].xmax, 1.5);
+            assert_eq!(tier.intervals[1].xmin, 1.5);
+
+            assert!(tier.move_boundary(0, 3.0).is_err());
+            assert!(tier.move_boundary(0, 0.0).is_err());
+            assert!(tier.move_boundary(1, 2.0).is_err());
+        }
+    }
+
+    mod lookup {
+        use crate::interval::{Interval, Tier};
+
+        fn tier() -> Tier {
+            Tier::new(
+                "test".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(1.0, 2.0, "daisy".to_string()),
+                    Interval::new(2.0, 3.0, "bell".to_string()),
+                ],
+            )
+        }
+
+        #[test]
+        fn interval_at() {
+            let tier = tier();
+
+            assert_eq!(tier.interval_index_at(0.0), Some(0));
+            assert_eq!(tier.interval_index_at(0.5), Some(0));
+            assert_eq!(tier.interval_index_at(1.0), Some(1));
+            assert_eq!(tier.interval_index_at(3.0), Some(2));
+            assert_eq!(tier.interval_index_at(3.5), None);
+            assert_eq!(tier.interval_index_at(-0.5), None);
+            assert_eq!(tier.interval_at(2.5).unwrap().text(), "bell");
+        }
+
+        #[test]
+        fn intervals_overlapping() {
+            let tier = tier();
+
+            let (start, intervals) = tier.intervals_overlapping(1.0, 2.5);
+            assert_eq!(start, 1);
+            assert_eq!(intervals.len(), 2);
+
+            assert_eq!(tier.intervals_overlapping(0.5, 0.7).1.len(), 1);
+            assert!(tier.intervals_overlapping(3.0, 4.0).1.is_empty());
+            assert!(tier.intervals_overlapping(2.0, 1.0).1.is_empty());
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
     mod check_overlaps {
         use crate::{
             interval::{Interval, Tier as IntervalTier},
             textgrid::{TextGrid, Tier},
+            tier::TierIssue,
         };
 
         #[test]
@@ -512,9 +1063,9 @@ mod test_tier {
                 false,
             );
 
-            let overlaps = textgrid.check_overlaps();
+            let overlaps = textgrid.check_overlaps(0.0);
 
-            assert!(overlaps.is_none());
+            assert!(overlaps.is_empty());
         }
 
         #[test]
@@ -534,11 +1085,85 @@ mod test_tier {
                 false,
             );
 
-            let overlaps = textgrid.check_overlaps().unwrap();
+            let overlaps = textgrid.check_overlaps(0.0);
 
             assert_eq!(overlaps.len(), 1);
             assert_eq!(overlaps[0].0, "John");
-            assert_eq!(overlaps[0].1, (0, 1));
+            assert_eq!(
+                overlaps[0].1,
+                TierIssue::Overlap {
+                    first: 0,
+                    second: 1,
+                    xmin: 1.0,
+                    xmax: 1.5,
+                }
+            );
+            assert_eq!(overlaps[0].1.magnitude(), 0.5);
+        }
+
+        #[test]
+        fn gaps_and_epsilon() {
+            let tier = IntervalTier::new(
+                "John".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(1.001, 2.0, "bell".to_string()),
+                    Interval::new(2.0, 2.0, String::new()),
+                    Interval::new(2.5, 3.5, "give".to_string()),
+                ],
+            );
+
+            let issues = tier.check_overlaps(0.0);
+            assert_eq!(issues.len(), 4);
+            assert!(matches!(
+                issues[0],
+                TierIssue::Gap {
+                    before: Some(0),
+                    after: Some(1),
+                    ..
+                }
+            ));
+            assert!(matches!(
+                issues[1],
+                TierIssue::ZeroOrNegativeDuration { index: 2, .. }
+            ));
+            assert_eq!(issues[2].span(), (2.0, 2.5));
+            assert_eq!(issues[3].magnitude(), 0.5);
+
+            assert_eq!(tier.check_overlaps(0.01).len(), 3);
+        }
+
+        #[test]
+        fn unsorted() {
+            let tier = IntervalTier::new(
+                "John".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(2.0, 3.0, "give".to_string()),
+                    Interval::new(1.0, 2.0, "bell".to_string()),
+                ],
+            );
+
+            assert_eq!(
+                tier.check_overlaps(0.0),
+                vec![
+                    TierIssue::Gap {
+                        before: Some(0),
+                        after: Some(1),
+                        xmin: 1.0,
+                        xmax: 2.0,
+                    },
+                    TierIssue::Unsorted {
+                        index: 2,
+                        xmin: 1.0,
+                        xmax: 2.0,
+                    },
+                ]
+            );
         }
     }
 
@@ -8,11 +8,34 @@ use std::{
     io::{Error, ErrorKind, Result},
 };
 
+pub mod agreement;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod chat;
+pub mod combine;
+pub mod ctm;
+pub mod diff;
+#[cfg(feature = "emu")]
+pub mod emu;
+pub mod hierarchy;
+pub mod index;
 mod input;
 pub mod interval;
+pub mod partitur;
 pub mod point;
+pub mod query;
+pub mod snap;
+pub mod sonic_visualiser;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stats;
 pub mod textgrid;
+pub mod three_way;
+pub mod tier;
+#[cfg(feature = "xml")]
+pub mod transcriber;
 mod utilities;
+pub mod validate;
 
 use input::Source;
 use interval::{Interval, Tier as IntervalTier};
@@ -25,10 +48,10 @@ use utilities::get_file_content;
 /// # Arguments
 ///
 /// * `input` - One of the following:
-///                 * A path to a `.TextGrid` file.
-///                 * A string containing the entire `TextGrid` file.
-///                 * A vector of strings containing the lines of a `.TextGrid` file.
-///                 * A stream containing the contents of a `.TextGrid` file.
+///   * A path to a `.TextGrid` file.
+///   * A string containing the entire `TextGrid` file.
+///   * A vector of strings containing the lines of a `.TextGrid` file.
+///   * A stream containing the contents of a `.TextGrid` file.
 /// * `print_warnings?` - An optional boolean indicating whether to print warnings.
 ///
 /// # Returns
@@ -190,13 +213,17 @@ fn parse_tiers<W: Into<Option<bool>> + Copy>(
             if xmin < tg_xmin {
                 return Err(Error::new(
                     ErrorKind::InvalidData,
-                    "TextGrid malformed; tier {tier_name} `xmin` less than TextGrid `xmin`",
+                    format!(
+                        "TextGrid malformed; tier {tier_name} `xmin` less than TextGrid `xmin`"
+                    ),
                 ));
             }
             if xmax > tg_xmax {
                 return Err(Error::new(
                     ErrorKind::InvalidData,
-                    "TextGrid malformed; tier {tier_name} `xmax` greater than TextGrid `xmax`",
+                    format!(
+                        "TextGrid malformed; tier {tier_name} `xmax` greater than TextGrid `xmax`"
+                    ),
                 ));
             }
         }
@@ -306,10 +333,9 @@ mod test {
     fn parse_textgrid_from_string() {
         let parsed_textgrid = parse_textgrid(TEXTGRID, false).unwrap();
 
-        let tier = match &parsed_textgrid.tiers()[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_textgrid.tiers()[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
@@ -318,10 +344,9 @@ mod test {
     fn parse_textgrid_from_path() {
         let parsed_textgrid = parse_textgrid("example/long.TextGrid", false).unwrap();
 
-        let tier = match &parsed_textgrid.tiers()[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_textgrid.tiers()[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
@@ -335,10 +360,9 @@ mod test {
 
         let parsed_textgrid = parse_textgrid(textgrid_vector, false).unwrap();
 
-        let tier = match &parsed_textgrid.tiers()[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_textgrid.tiers()[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
@@ -350,10 +374,9 @@ mod test {
         let parsed_textgrid =
             parse_textgrid(Source::Stream(Box::new(textgrid_stream)), false).unwrap();
 
-        let tier = match &parsed_textgrid.tiers()[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_textgrid.tiers()[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
@@ -364,10 +387,9 @@ mod test {
 
         let parsed_textgrid = parse_textgrid(textgrid_file, false).unwrap();
 
-        let tier = match &parsed_textgrid.tiers()[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_textgrid.tiers()[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
@@ -420,10 +442,9 @@ mod test {
 
         let parsed_tiers = super::parse_tiers(&mut tier_data, 0.0, 2.3, false).unwrap();
 
-        let tier = match &parsed_tiers[1] {
-            crate::textgrid::Tier::IntervalTier(tier) => tier,
-            crate::textgrid::Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
-        };
+        let tier = parsed_tiers[1]
+            .as_interval()
+            .expect("Expected IntervalTier, got PointTier");
 
         assert_eq!(tier.name(), "Kelly");
     }
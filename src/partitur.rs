@@ -0,0 +1,353 @@
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::{
+    input::Source,
+    interval::{Interval, Tier as IntervalTier},
+    textgrid::{TextGrid, Tier},
+    utilities::{contiguous_tier, get_file_content},
+};
+
+/// An `ORT` label and its optional `KAN` label.
+type Word = (String, Option<String>);
+
+/// A `MAU`/`TRN` segment: begin sample, duration in samples, word indices and label.
+type Segment = (i64, i64, Vec<i64>, String);
+
+/// Parses a BAS Partitur (`.par`) file into a `TextGrid`.
+///
+/// `MAU` and `TRN` segments are converted from samples to seconds using the `SAM` header.
+/// `ORT` and `KAN` words have no times of their own, so each word spans the `MAU` segments
+/// linked to its index (or the `TRN` segments if there is no `MAU` tier). Each tier type
+/// present in the file becomes an `IntervalTier` of the same name, with gaps filled by
+/// empty intervals.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, if the `SAM` header is missing, or if a
+/// tier line is malformed.
+pub fn parse_partitur<I: Into<Source>>(input: I) -> Result<TextGrid> {
+    let (content, name) = get_file_content(input.into())?;
+
+    let mut sample_rate = None::<f64>;
+    let mut words = BTreeMap::<i64, Word>::new();
+    let mut mau = Vec::<Segment>::new();
+    let mut trn = Vec::<Segment>::new();
+
+    for (line_number, line) in content.iter().enumerate() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match key {
+            "SAM" => {
+                sample_rate =
+                    Some(value.parse::<f64>().map_err(|_| {
+                        malformed(line_number, "could not parse `SAM` as a number")
+                    })?);
+            }
+            "ORT" | "KAN" => {
+                let (index, label) = value
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| malformed(line_number, "expected a word index and a label"))?;
+                let index = parse_index(index, line_number)?;
+                let word = words.entry(index).or_default();
+                if key == "ORT" {
+                    word.0 = label.trim().to_string();
+                } else {
+                    word.1 = Some(label.trim().to_string());
+                }
+            }
+            "MAU" => mau.push(parse_segment(value, line_number)?),
+            "TRN" => trn.push(parse_segment(value, line_number)?),
+            _ => {}
+        }
+    }
+
+    let sample_rate = sample_rate.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "Partitur malformed; missing `SAM` header",
+        )
+    })?;
+    let to_seconds = |sample: i64| {
+        #[allow(clippy::cast_precision_loss)]
+        let sample = sample as f64;
+        sample / sample_rate
+    };
+
+    let xmax = mau
+        .iter()
+        .chain(&trn)
+        .map(|(begin, duration, _, _)| to_seconds(begin + duration + 1))
+        .fold(0.0, f64::max);
+
+    // Word spans come from the linked MAU segments, or from TRN if there are none
+    let mut word_spans = BTreeMap::<i64, (f64, f64)>::new();
+    for (begin, duration, indices, _) in if mau.is_empty() { &trn } else { &mau } {
+        for index in indices {
+            let span = word_spans.entry(*index).or_insert((f64::INFINITY, 0.0));
+            span.0 = span.0.min(to_seconds(*begin));
+            span.1 = span.1.max(to_seconds(begin + duration + 1));
+        }
+    }
+
+    let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), name);
+
+    if !words.is_empty() {
+        let ort = word_tier("ORT", xmax, &words, &word_spans, |word| {
+            Some(word.0.clone())
+        });
+        textgrid.push_tier(Tier::IntervalTier(ort), false);
+
+        if words.values().any(|word| word.1.is_some()) {
+            let kan = word_tier("KAN", xmax, &words, &word_spans, |word| word.1.clone());
+            textgrid.push_tier(Tier::IntervalTier(kan), false);
+        }
+    }
+
+    for (tier_name, segments) in [("MAU", &mau), ("TRN", &trn)] {
+        if segments.is_empty() {
+            continue;
+        }
+
+        let spans = segments
+            .iter()
+            .map(|(begin, duration, _, label)| {
+                (
+                    to_seconds(*begin),
+                    to_seconds(begin + duration + 1),
+                    label.clone(),
+                )
+            })
+            .collect();
+
+        textgrid.push_tier(
+            Tier::IntervalTier(contiguous_tier(tier_name, xmax, spans)),
+            false,
+        );
+    }
+
+    Ok(textgrid)
+}
+
+/// Formats a word tier and a phone tier as a BAS Partitur file with `ORT` and `MAU` tiers.
+///
+/// Every word interval with non-empty text becomes an `ORT` line. Every phone interval
+/// becomes a `MAU` line linked to the word containing its midpoint, or to `-1` if there is
+/// none. Phone intervals with empty text are written as the pause symbol `<p:>`.
+///
+/// # Arguments
+///
+/// * `words` - The word tier.
+/// * `phones` - The phone tier.
+/// * `sample_rate` - The sample rate used to convert seconds into samples.
+///
+/// # Returns
+///
+/// A vector of strings containing the lines of the Partitur file.
+#[must_use]
+pub fn format_partitur(
+    words: &IntervalTier,
+    phones: &IntervalTier,
+    sample_rate: u32,
+) -> Vec<String> {
+    let to_samples = |seconds: f64| {
+        #[allow(clippy::cast_possible_truncation)]
+        let samples = (seconds * f64::from(sample_rate)).round() as i64;
+        samples
+    };
+
+    let mut out_strings: Vec<String> = vec![
+        "LHD: Partitur 1.3".into(),
+        format!("SAM: {sample_rate}"),
+        "LBD:".into(),
+    ];
+
+    let spoken_words = words
+        .intervals()
+        .iter()
+        .filter(|interval| !interval.text().trim().is_empty())
+        .collect::<Vec<&Interval>>();
+
+    for (index, word) in spoken_words.iter().enumerate() {
+        out_strings.push(format!("ORT: {index} {}", word.text().trim()));
+    }
+
+    for phone in phones.intervals() {
+        let begin = to_samples(*phone.xmin());
+        let duration = to_samples(*phone.xmax()) - begin - 1;
+        let midpoint = phone.get_midpoint();
+        let word_index = spoken_words
+            .iter()
+            .position(|word| *word.xmin() <= midpoint && midpoint < *word.xmax())
+            .map_or(-1, |index| i64::try_from(index).unwrap_or(-1));
+        let label = if phone.text().trim().is_empty() {
+            "<p:>"
+        } else {
+            phone.text().trim()
+        };
+
+        out_strings.push(format!("MAU: {begin} {duration} {word_index} {label}"));
+    }
+
+    out_strings
+}
+
+/// Builds an `ORT`-style tier from the words that have a time span, labelled by `label_of`.
+fn word_tier<F: Fn(&Word) -> Option<String>>(
+    name: &str,
+    xmax: f64,
+    words: &BTreeMap<i64, Word>,
+    word_spans: &BTreeMap<i64, (f64, f64)>,
+    label_of: F,
+) -> IntervalTier {
+    let spans = words
+        .iter()
+        .filter_map(|(index, word)| {
+            let (start, end) = word_spans.get(index)?;
+            Some((*start, *end, label_of(word)?))
+        })
+        .collect();
+
+    contiguous_tier(name, xmax, spans)
+}
+
+/// Parses a `MAU`/`TRN` line value: `<begin> <duration> <index[,index...]> <label>`.
+fn parse_segment(value: &str, line_number: usize) -> Result<Segment> {
+    let mut fields = value.splitn(4, char::is_whitespace);
+
+    let begin = fields
+        .next()
+        .and_then(|field| field.parse::<i64>().ok())
+        .ok_or_else(|| malformed(line_number, "could not parse segment begin"))?;
+    let duration = fields
+        .next()
+        .and_then(|field| field.parse::<i64>().ok())
+        .ok_or_else(|| malformed(line_number, "could not parse segment duration"))?;
+    let indices = fields
+        .next()
+        .ok_or_else(|| malformed(line_number, "expected word indices"))?
+        .split(',')
+        .map(|index| parse_index(index, line_number))
+        .collect::<Result<Vec<i64>>>()?;
+    let label = fields.next().unwrap_or_default().trim().to_string();
+
+    Ok((begin, duration, indices, label))
+}
+
+/// Parses a word index.
+fn parse_index(index: &str, line_number: usize) -> Result<i64> {
+    index
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| malformed(line_number, "could not parse word index"))
+}
+
+/// Builds an error for a malformed Partitur line.
+fn malformed(line_number: usize, message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Partitur malformed; line {}: {message}", line_number + 1),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_partitur {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        partitur::{format_partitur, parse_partitur},
+        textgrid::Tier,
+    };
+
+    const PARTITUR: &str = "LHD: Partitur 1.3\nSAM: 100\nLBD:\nORT: 0 guten\nORT: 1 Tag\nKAN: 0 g'u:t@n\nKAN: 1 t'a:k\nMAU: 0 9 -1 <p:>\nMAU: 10 9 0 g\nMAU: 20 19 0 u:t@n\nMAU: 40 19 1 ta:k\nTRN: 10 49 0,1 guten Tag\n";
+
+    fn interval_tier(tier: &Tier) -> &IntervalTier {
+        match tier {
+            Tier::IntervalTier(tier) => tier,
+            Tier::PointTier(_) => panic!("Expected IntervalTier, got PointTier"),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let textgrid = parse_partitur(PARTITUR).unwrap();
+
+        assert_eq!(textgrid.xmax(), &0.6);
+        assert_eq!(textgrid.get_size(), 4);
+
+        let ort = interval_tier(textgrid.get_tier("ORT").unwrap());
+        assert_eq!(ort.get_size(), 3);
+        assert_eq!(ort.intervals()[1].text(), "guten");
+        assert_eq!(ort.intervals()[1].xmin(), &0.1);
+        assert_eq!(ort.intervals()[1].xmax(), &0.4);
+        assert_eq!(ort.intervals()[2].text(), "Tag");
+
+        let kan = interval_tier(textgrid.get_tier("KAN").unwrap());
+        assert_eq!(kan.intervals()[2].text(), "t'a:k");
+
+        let mau = interval_tier(textgrid.get_tier("MAU").unwrap());
+        assert_eq!(mau.get_size(), 4);
+        assert_eq!(mau.intervals()[0].text(), "<p:>");
+
+        let trn = interval_tier(textgrid.get_tier("TRN").unwrap());
+        assert_eq!(trn.intervals()[1].text(), "guten Tag");
+    }
+
+    #[test]
+    fn parse_without_sample_rate() {
+        assert!(parse_partitur("LHD: Partitur 1.3\nORT: 0 guten\n").is_err());
+    }
+
+    #[test]
+    fn format() {
+        let words = IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            0.6,
+            vec![
+                Interval::new(0.0, 0.1, String::new()),
+                Interval::new(0.1, 0.4, "guten".to_string()),
+                Interval::new(0.4, 0.6, "Tag".to_string()),
+            ],
+        );
+        let phones = IntervalTier::new(
+            "phones".to_string(),
+            0.0,
+            0.6,
+            vec![
+                Interval::new(0.0, 0.1, String::new()),
+                Interval::new(0.1, 0.4, "gu:t@n".to_string()),
+                Interval::new(0.4, 0.6, "ta:k".to_string()),
+            ],
+        );
+
+        let partitur = format_partitur(&words, &phones, 100);
+
+        assert_eq!(
+            partitur,
+            vec![
+                "LHD: Partitur 1.3",
+                "SAM: 100",
+                "LBD:",
+                "ORT: 0 guten",
+                "ORT: 1 Tag",
+                "MAU: 0 9 -1 <p:>",
+                "MAU: 10 29 0 gu:t@n",
+                "MAU: 40 19 1 ta:k",
+            ]
+        );
+
+        let textgrid = parse_partitur(partitur).unwrap();
+        let ort = interval_tier(textgrid.get_tier("ORT").unwrap());
+        assert_eq!(ort.intervals()[2].xmin(), &0.4);
+    }
+}
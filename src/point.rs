@@ -1,11 +1,17 @@
 use std::{
     cmp::Ordering,
     fmt::{self, Display, Formatter},
+    io::Result,
 };
 
 use derive_more::Constructor;
 use getset::{Getters, Setters};
 
+use crate::{
+    tier::{Annotation, AnnotationTier, TierIssue},
+    utilities::check_scale_factor,
+};
+
 /// A "point," used in Praat as a specific time marker with an associated label.
 #[derive(Constructor, Debug, Default, Clone, Getters, Setters)]
 pub struct Point {
@@ -150,26 +156,192 @@ impl Tier {
         self.reorder();
     }
 
-    /// Checks for overlaps in the tier.
+    /// Checks the tier for duplicate points, points outside the tier's bounds and points that
+    /// come before the previous one, in linear time.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - Points at most `epsilon` seconds apart count as duplicates, and overshoots
+    ///   and backward steps of at most `epsilon` seconds are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The issues found, in order of time.
+    #[must_use]
+    pub fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue> {
+        let mut issues = Vec::new();
+
+        for (index, point) in self.points.iter().enumerate() {
+            if index > 0 {
+                let previous = self.points[index - 1].number;
+                let distance = point.number - previous;
+                if distance < -epsilon {
+                    issues.push(TierIssue::Unsorted {
+                        index,
+                        xmin: point.number,
+                        xmax: previous,
+                    });
+                } else if distance <= epsilon {
+                    issues.push(TierIssue::Duplicate {
+                        first: index - 1,
+                        second: index,
+                        time: point.number,
+                        distance: distance.abs(),
+                    });
+                }
+            }
+
+            let overshoot = (self.xmin - point.number).max(point.number - self.xmax);
+            if overshoot > epsilon {
+                issues.push(TierIssue::OutOfTierBounds {
+                    index,
+                    xmin: point.number,
+                    xmax: point.number,
+                    overshoot,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Edits the points of the tier in place.
+    /// Afterwards, calls `reorder()` and widens the tier's bounds to cover every point, so the
+    /// tier stays sorted and within bounds whatever `edit` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - A closure given mutable access to the points.
+    ///
+    /// # Returns
+    ///
+    /// The value returned by `edit`.
+    pub fn edit_points<R, F: FnOnce(&mut Vec<Point>) -> R>(&mut self, edit: F) -> R {
+        let result = edit(&mut self.points);
+
+        self.reorder();
+        for point in &self.points {
+            self.xmin = self.xmin.min(point.number);
+            self.xmax = self.xmax.max(point.number);
+        }
+
+        result
+    }
+
+    /// Finds the points within `[t0, t1)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `t0` - The start of the range.
+    /// * `t1` - The end of the range.
+    ///
+    /// # Returns
+    ///
+    /// The points in the range, in order, with the index of the first one.
+    #[must_use]
+    pub fn points_in(&self, t0: f64, t1: f64) -> (usize, &[Point]) {
+        let start = self.points.partition_point(|point| point.number < t0);
+        let end = self
+            .points
+            .partition_point(|point| point.number < t1)
+            .max(start);
+
+        (start, &self.points[start..end])
+    }
+
+    /// Finds the point nearest to time `t`. If two points are equally near, the earlier one is
+    /// chosen.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time to look up.
     ///
     /// # Returns
     ///
-    /// A vector of the indices of the overlapping points or `None` if there are no overlaps.
+    /// The index of the nearest point and the point itself, or `None` if the tier has no points.
     #[must_use]
-    pub fn check_overlaps(&self) -> Option<Vec<(u64, u64)>> {
-        let mut overlaps: Vec<(u64, u64)> = Vec::new();
-        for (i, point) in self.points.iter().enumerate() {
-            for (j, other_point) in self.points.iter().enumerate() {
-                #[allow(clippy::float_cmp)]
-                if i != j && point.number == other_point.number {
-                    overlaps.push((i as u64, j as u64));
+    pub fn nearest_point(&self, t: f64) -> Option<(usize, &Point)> {
+        let after = self.points.partition_point(|point| point.number < t);
+
+        let index = match (after.checked_sub(1), self.points.get(after)) {
+            (Some(before), Some(next)) => {
+                if t - self.points[before].number <= next.number - t {
+                    before
+                } else {
+                    after
                 }
             }
+            (Some(before), None) => before,
+            (None, Some(_)) => after,
+            (None, None) => return None,
+        };
+
+        Some((index, &self.points[index]))
+    }
+
+    /// Shifts every time in the tier, including its bounds, by `dt` seconds, like Praat's
+    /// "Shift times by".
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The number of seconds to shift by. Negative values shift towards zero.
+    pub fn shift(&mut self, dt: f64) {
+        self.map_times(|t| t + dt);
+    }
+
+    /// Scales every time in the tier, including its bounds, by `factor` around `anchor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The scaling factor.
+    /// * `anchor` - The time that stays in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `factor` is not positive and finite.
+    pub fn scale(&mut self, factor: f64, anchor: f64) -> Result<()> {
+        check_scale_factor(factor)?;
+        self.map_times(|t| (t - anchor).mul_add(factor, anchor));
+
+        Ok(())
+    }
+
+    /// Extracts the part of the tier between `t0` and `t1`, like Praat's "Extract part".
+    /// Points outside the part are dropped; points exactly at `t0` or `t1` are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `t0` - The start of the part.
+    /// * `t1` - The end of the part.
+    /// * `preserve_times` - If `true`, keeps the original times. If `false`, shifts the part so
+    ///   that it starts at zero.
+    ///
+    /// # Returns
+    ///
+    /// A new point tier spanning `t0` to `t1`, or `0.0` to `t1 - t0`.
+    #[must_use]
+    pub fn extract_part(&self, t0: f64, t1: f64, preserve_times: bool) -> Self {
+        let points = self
+            .points
+            .iter()
+            .filter(|point| t0 <= point.number && point.number <= t1)
+            .cloned()
+            .collect();
+
+        let mut part = Self::new(self.name.clone(), t0, t1, points);
+        if !preserve_times {
+            part.shift(-t0);
         }
-        if overlaps.is_empty() {
-            None
-        } else {
-            Some(overlaps)
+
+        part
+    }
+
+    /// Applies `map` to every time in the tier, including its bounds.
+    fn map_times<F: Fn(f64) -> f64>(&mut self, map: F) {
+        self.xmin = map(self.xmin);
+        self.xmax = map(self.xmax);
+        for point in &mut self.points {
+            point.number = map(point.number);
         }
     }
 
@@ -180,6 +352,57 @@ impl Tier {
     }
 }
 
+impl AnnotationTier for Tier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn xmin(&self) -> f64 {
+        self.xmin
+    }
+
+    fn xmax(&self) -> f64 {
+        self.xmax
+    }
+
+    fn set_xmin(&mut self, xmin: f64, warn: Option<bool>) {
+        Self::set_xmin(self, xmin, warn);
+    }
+
+    fn set_xmax(&mut self, xmax: f64, warn: Option<bool>) {
+        Self::set_xmax(self, xmax, warn);
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn annotations(&self) -> Box<dyn Iterator<Item = Annotation<'_>> + '_> {
+        Box::new(
+            self.points
+                .iter()
+                .enumerate()
+                .map(|(index, point)| Annotation::point(index, point.number, &point.mark)),
+        )
+    }
+
+    fn annotations_in(&self, t0: f64, t1: f64) -> Vec<Annotation<'_>> {
+        let (start, points) = self.points_in(t0, t1);
+        (start..)
+            .zip(points)
+            .map(|(index, point)| Annotation::point(index, point.number, &point.mark))
+            .collect()
+    }
+
+    fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue> {
+        Self::check_overlaps(self, epsilon)
+    }
+}
+
 impl Display for Tier {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -259,7 +482,10 @@ mod test_point_tier {
 
     #[test]
     fn check_overlaps() {
-        use crate::point::{Point, Tier};
+        use crate::{
+            point::{Point, Tier},
+            tier::TierIssue,
+        };
 
         let mut tier = Tier::new("test".to_string(), 0.0, 10.0, vec![]);
         tier.push_points(
@@ -269,7 +495,113 @@ mod test_point_tier {
             ],
             true,
         );
-        assert_eq!(tier.check_overlaps(), Some(vec![(0, 1), (1, 0)]));
+        assert_eq!(
+            tier.check_overlaps(0.0),
+            vec![TierIssue::Duplicate {
+                first: 0,
+                second: 1,
+                time: 5.0,
+                distance: 0.0,
+            }]
+        );
+
+        tier.push_points(
+            vec![
+                Point::new(5.001, "test".to_string()),
+                Point::new(11.0, "test".to_string()),
+            ],
+            false,
+        );
+        assert_eq!(tier.check_overlaps(0.0).len(), 2);
+        assert_eq!(tier.check_overlaps(0.01).len(), 3);
+        assert!(matches!(
+            tier.check_overlaps(0.0)[1],
+            TierIssue::OutOfTierBounds { index: 3, .. }
+        ));
+
+        let unsorted = Tier::new(
+            "test".to_string(),
+            0.0,
+            10.0,
+            vec![
+                Point::new(2.0, "test".to_string()),
+                Point::new(1.0, "test".to_string()),
+                Point::new(3.0, "test".to_string()),
+            ],
+        );
+        assert_eq!(
+            unsorted.check_overlaps(0.01),
+            vec![TierIssue::Unsorted {
+                index: 1,
+                xmin: 1.0,
+                xmax: 2.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn edit_points() {
+        use crate::point::{Point, Tier};
+
+        let mut tier = Tier::new(
+            "test".to_string(),
+            0.0,
+            10.0,
+            vec![Point::new(5.0, "test".to_string())],
+        );
+        tier.edit_points(|points| {
+            points[0].set_mark("H*".to_string());
+            points.push(Point::new(-1.0, "L%".to_string()));
+        });
+        assert_eq!(tier.points()[0].mark(), "L%");
+        assert_eq!(tier.points()[1].mark(), "H*");
+        assert_eq!(tier.xmin(), &-1.0);
+    }
+
+    #[test]
+    fn points_in() {
+        use crate::point::{Point, Tier};
+
+        let tier = Tier::new(
+            "test".to_string(),
+            0.0,
+            10.0,
+            vec![
+                Point::new(1.0, "a".to_string()),
+                Point::new(2.0, "b".to_string()),
+                Point::new(3.0, "c".to_string()),
+            ],
+        );
+
+        let (start, points) = tier.points_in(2.0, 3.0);
+        assert_eq!(start, 1);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].mark(), "b");
+        assert!(tier.points_in(3.5, 9.0).1.is_empty());
+        assert!(tier.points_in(3.0, 2.0).1.is_empty());
+    }
+
+    #[test]
+    fn nearest_point() {
+        use crate::point::{Point, Tier};
+
+        let tier = Tier::new(
+            "test".to_string(),
+            0.0,
+            10.0,
+            vec![
+                Point::new(1.0, "a".to_string()),
+                Point::new(2.0, "b".to_string()),
+            ],
+        );
+
+        assert_eq!(tier.nearest_point(1.6).unwrap().0, 1);
+        assert_eq!(tier.nearest_point(1.5).unwrap().0, 0);
+        assert_eq!(tier.nearest_point(-4.0).unwrap().0, 0);
+        assert_eq!(tier.nearest_point(9.0).unwrap().0, 1);
+        assert!(Tier::new("test".to_string(), 0.0, 10.0, vec![])
+            .nearest_point(1.0)
+            .is_none());
     }
 
     #[test]
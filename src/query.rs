@@ -0,0 +1,669 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{Error, ErrorKind, Result},
+    iter::Peekable,
+    str::FromStr,
+    vec::IntoIter,
+};
+
+use getset::Getters;
+use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    index::TierIndex,
+    textgrid::{TextGrid, Tier},
+    tier::{Annotation, AnnotationTier},
+};
+
+/// How a number is compared with a bound in a `Predicate`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    /// Returns `true` if `value` compares with `bound` as required.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn holds(self, value: f64, bound: f64) -> bool {
+        match self {
+            Self::Less => value < bound,
+            Self::LessOrEqual => value <= bound,
+            Self::Greater => value > bound,
+            Self::GreaterOrEqual => value >= bound,
+            Self::Equal => value == bound,
+        }
+    }
+}
+
+/// A condition on a single interval or point.
+#[derive(Clone, Debug)]
+pub enum Predicate {
+    /// The text or mark matches a regular expression.
+    Label(Regex),
+    /// The duration in seconds compares with a bound. Points last 0 seconds.
+    Duration(Comparison, f64),
+    /// The start time compares with a bound.
+    Start(Comparison, f64),
+    /// The end time compares with a bound.
+    End(Comparison, f64),
+}
+
+impl Predicate {
+    /// Creates a predicate matching labels that contain a match for a regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn label(pattern: &str) -> Result<Self> {
+        Regex::new(pattern)
+            .map(Self::Label)
+            .map_err(|error| Error::new(ErrorKind::InvalidInput, error))
+    }
+
+    /// Creates a predicate matching labels equal to `text`.
+    ///
+    /// # Panics
+    ///
+    /// Never, as an escaped literal is always a valid regular expression.
+    #[must_use]
+    pub fn label_equals(text: &str) -> Self {
+        Self::Label(Regex::new(&format!("^{}$", regex::escape(text))).unwrap()) // Unwrap is safe here
+    }
+
+    /// Returns `true` if an interval or point satisfies the predicate.
+    #[must_use]
+    pub fn holds(&self, annotation: &Annotation) -> bool {
+        match self {
+            Self::Label(regex) => regex.is_match(annotation.text()),
+            Self::Duration(comparison, bound) => {
+                comparison.holds(annotation.xmax() - annotation.xmin(), *bound)
+            }
+            Self::Start(comparison, bound) => comparison.holds(annotation.xmin(), *bound),
+            Self::End(comparison, bound) => comparison.holds(annotation.xmax(), *bound),
+        }
+    }
+}
+
+/// Where to look for the intervals or points a `Query` context refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Context {
+    /// The interval or point just before the match on its own tier.
+    Previous,
+    /// The interval or point just after the match on its own tier.
+    Next,
+    /// An interval on another tier that the match lies within. A point lies within the
+    /// interval `[xmin, xmax)` it falls in.
+    Within(String),
+    /// An interval or point on another tier that lies within the match.
+    Contains(String),
+    /// An interval or point on another tier that shares any time with the match, as defined by
+    /// `Annotation::intersects`.
+    Overlaps(String),
+}
+
+impl Context {
+    /// Returns the other tier the context looks in, if any.
+    #[must_use]
+    pub fn tier(&self) -> Option<&str> {
+        match self {
+            Self::Previous | Self::Next => None,
+            Self::Within(tier) | Self::Contains(tier) | Self::Overlaps(tier) => Some(tier),
+        }
+    }
+}
+
+/// An interval or point found by a `Query`.
+#[derive(Clone, Debug, Getters, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QueryMatch {
+    /// The name of the tier the match is on.
+    #[getset(get = "pub")]
+    tier: String,
+    /// The index of the interval or point on its tier.
+    #[getset(get = "pub")]
+    index: usize,
+    #[getset(get = "pub")]
+    xmin: f64,
+    #[getset(get = "pub")]
+    xmax: f64,
+    /// The text of the interval or the mark of the point.
+    #[getset(get = "pub")]
+    text: String,
+}
+
+impl Display for QueryMatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.tier, self.index, self.xmin, self.xmax, self.text
+        )
+    }
+}
+
+/// A search for the intervals or points of one tier that satisfy a set of predicates, both on
+/// themselves and on related intervals or points.
+///
+/// Queries can be built in Rust:
+///
+/// ```
+/// use textgridde_rs::query::{Comparison, Context, Predicate, Query};
+///
+/// let query = Query::new("phones")
+///     .matching(Predicate::label("^[aeiou]$").unwrap())
+///     .matching(Predicate::Duration(Comparison::Greater, 0.05))
+///     .with_context(Context::Previous, vec![Predicate::label("^[mn]$").unwrap()])
+///     .with_context(
+///         Context::Within("words".to_string()),
+///         vec![Predicate::label_equals("the")],
+///     );
+/// ```
+///
+/// or parsed from text, which is how the `textgrid-query` binary takes them:
+///
+/// ```text
+/// phones where label ~ "^[aeiou]$" and duration > 50ms
+///     and prev label ~ "^[mn]$" and within words label = "the"
+/// ```
+///
+/// A query names the tier to search, then after `where` lists clauses joined by `and`. Each
+/// clause is an optional context (`prev`, `next`, `within TIER`, `contains TIER` or
+/// `overlaps TIER`) followed by a predicate: `label ~ REGEX`, `label = TEXT`, or `duration`,
+/// `start` or `end` compared with `<`, `<=`, `>`, `>=` or `=` to a number of seconds, which may
+/// end in `s` or `ms`. Clauses with the same context must all hold for the same related
+/// interval or point.
+#[derive(Clone, Debug)]
+pub struct Query {
+    tier: String,
+    predicates: Vec<Predicate>,
+    contexts: Vec<(Context, Vec<Predicate>)>,
+}
+
+impl Query {
+    /// Creates a query matching every interval or point of a tier.
+    #[must_use]
+    pub fn new(tier: &str) -> Self {
+        Self {
+            tier: tier.to_string(),
+            predicates: Vec::new(),
+            contexts: Vec::new(),
+        }
+    }
+
+    /// Adds a predicate the matches must satisfy.
+    #[must_use]
+    pub fn matching(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Requires the matches to have a related interval or point, found through `context`, that
+    /// satisfies every one of `predicates`.
+    #[must_use]
+    pub fn with_context(mut self, context: Context, predicates: Vec<Predicate>) -> Self {
+        match self
+            .contexts
+            .iter_mut()
+            .find(|(other, _)| *other == context)
+        {
+            Some((_, existing)) => existing.extend(predicates),
+            None => self.contexts.push((context, predicates)),
+        }
+        self
+    }
+
+    /// Runs the query against a `TextGrid`.
+    ///
+    /// # Returns
+    ///
+    /// The matching intervals or points, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query refers to a tier that does not exist.
+    pub fn run(&self, textgrid: &TextGrid) -> Result<Vec<QueryMatch>> {
+        let get_tier = |name: &str| {
+            textgrid.get_tier(name).ok_or_else(|| {
+                Error::new(ErrorKind::NotFound, format!("Tier `{name}` does not exist"))
+            })
+        };
+
+        let tier = get_tier(&self.tier)?;
+        let annotations = tier.annotations().collect::<Vec<Annotation>>();
+        let indexes = self
+            .contexts
+            .iter()
+            .map(|(context, _)| context.tier().map(get_tier).transpose())
+            .collect::<Result<Vec<Option<&Tier>>>>()?
+            .into_iter()
+            .map(|tier| tier.map(TierIndex::new))
+            .collect::<Vec<Option<TierIndex>>>();
+
+        let satisfies = |predicates: &[Predicate], annotation: &Annotation| {
+            predicates
+                .iter()
+                .all(|predicate| predicate.holds(annotation))
+        };
+
+        Ok(annotations
+            .iter()
+            .filter(|annotation| satisfies(&self.predicates, annotation))
+            .filter(|annotation| {
+                self.contexts
+                    .iter()
+                    .zip(&indexes)
+                    .all(|((context, predicates), index)| {
+                        let related = match (context, index) {
+                            (Context::Previous, _) => annotation
+                                .index()
+                                .checked_sub(1)
+                                .and_then(|i| annotations.get(i))
+                                .copied()
+                                .into_iter()
+                                .collect(),
+                            (Context::Next, _) => annotations
+                                .get(annotation.index() + 1)
+                                .copied()
+                                .into_iter()
+                                .collect(),
+                            (_, Some(index)) => index
+                                .intersecting(annotation)
+                                .into_iter()
+                                .filter(|other| is_related(context, annotation, other))
+                                .collect(),
+                            (_, None) => Vec::new(),
+                        };
+                        related.iter().any(|other| satisfies(predicates, other))
+                    })
+            })
+            .map(|annotation| QueryMatch {
+                tier: self.tier.clone(),
+                index: annotation.index(),
+                xmin: annotation.xmin(),
+                xmax: annotation.xmax(),
+                text: annotation.text().to_string(),
+            })
+            .collect())
+    }
+}
+
+/// Returns `true` if `other`, which intersects `annotation`, is related to it by `context`.
+fn is_related(context: &Context, annotation: &Annotation, other: &Annotation) -> bool {
+    let contains = |outer: &Annotation, inner: &Annotation| {
+        outer.xmin() <= inner.xmin() && inner.xmax() <= outer.xmax()
+    };
+    match context {
+        Context::Within(_) => annotation.is_point() || contains(other, annotation),
+        Context::Contains(_) => other.is_point() || contains(annotation, other),
+        _ => true,
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(query: &str) -> Result<Self> {
+        let mut tokens = tokenize(query)?.into_iter().peekable();
+
+        let mut parsed = Self::new(&expect_text(&mut tokens, "a tier name")?);
+        match tokens.next() {
+            None => return Ok(parsed),
+            Some(Token::Word(word)) if word == "where" => {}
+            Some(token) => return Err(unexpected("`where`", &token)),
+        }
+
+        loop {
+            let context = parse_context(&mut tokens)?;
+            let predicate = parse_predicate(&mut tokens)?;
+            parsed = match context {
+                Some(context) => parsed.with_context(context, vec![predicate]),
+                None => parsed.matching(predicate),
+            };
+
+            match tokens.next() {
+                None => return Ok(parsed),
+                Some(Token::Word(word)) if word == "and" => {}
+                Some(token) => return Err(unexpected("`and`", &token)),
+            }
+        }
+    }
+}
+
+impl TextGrid {
+    /// Parses and runs a query written in the text form described on `Query`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query is malformed or refers to a tier that does not exist.
+    pub fn query(&self, query: &str) -> Result<Vec<QueryMatch>> {
+        query.parse::<Query>()?.run(self)
+    }
+}
+
+/// A token of the text form of a query.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// A bare word or number.
+    Word(String),
+    /// A double-quoted string, with `\"` and `\\` unescaped. Any other backslash is kept, so
+    /// regular expression escapes such as `\d` can be written as is.
+    Quoted(String),
+    /// One of `~`, `=`, `<`, `<=`, `>` or `>=`.
+    Operator(String),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Word(word) | Self::Operator(word) => write!(f, "`{word}`"),
+            Self::Quoted(text) => write!(f, "\"{text}\""),
+        }
+    }
+}
+
+/// Splits the text form of a query into tokens.
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next_if(|c| matches!(c, '"' | '\\')) {
+                        Some(escaped) => text.push(escaped),
+                        None => text.push('\\'),
+                    },
+                    Some(c) => text.push(c),
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            "Unterminated quoted string in query",
+                        ))
+                    }
+                }
+            }
+            tokens.push(Token::Quoted(text));
+        } else if "~=<>".contains(c) {
+            chars.next();
+            let mut operator = c.to_string();
+            if "<>".contains(c) && chars.next_if_eq(&'=').is_some() {
+                operator.push('=');
+            }
+            tokens.push(Token::Operator(operator));
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"\"~=<>".contains(*c)) {
+                word.push(c);
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn unexpected(expected: &str, found: &Token) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Expected {expected} in query, found {found}"),
+    )
+}
+
+fn missing(expected: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Expected {expected} at the end of the query"),
+    )
+}
+
+fn expect_text(tokens: &mut Peekable<IntoIter<Token>>, expected: &str) -> Result<String> {
+    match tokens.next() {
+        Some(Token::Word(text) | Token::Quoted(text)) => Ok(text),
+        Some(token) => Err(unexpected(expected, &token)),
+        None => Err(missing(expected)),
+    }
+}
+
+fn parse_context(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Option<Context>> {
+    let Some(Token::Word(word)) = tokens.peek() else {
+        return Ok(None);
+    };
+
+    let context = match word.as_str() {
+        "prev" | "previous" => Context::Previous,
+        "next" => Context::Next,
+        "within" | "contains" | "overlaps" => {
+            let word = word.clone();
+            tokens.next();
+            let tier = expect_text(tokens, "a tier name")?;
+            return Ok(Some(match word.as_str() {
+                "within" => Context::Within(tier),
+                "contains" => Context::Contains(tier),
+                _ => Context::Overlaps(tier),
+            }));
+        }
+        _ => return Ok(None),
+    };
+    tokens.next();
+
+    Ok(Some(context))
+}
+
+fn parse_predicate(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Predicate> {
+    let field = expect_text(tokens, "`label`, `duration`, `start` or `end`")?;
+    let operator = match tokens.next() {
+        Some(Token::Operator(operator)) => operator,
+        Some(token) => return Err(unexpected("an operator", &token)),
+        None => return Err(missing("an operator")),
+    };
+
+    if field == "label" {
+        let text = expect_text(tokens, "a label")?;
+        return match operator.as_str() {
+            "~" => Predicate::label(&text),
+            "=" => Ok(Predicate::label_equals(&text)),
+            _ => Err(unexpected("`~` or `=`", &Token::Operator(operator))),
+        };
+    }
+
+    let comparison = match operator.as_str() {
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        "=" => Comparison::Equal,
+        _ => return Err(unexpected("a comparison", &Token::Operator(operator))),
+    };
+    let number = expect_text(tokens, "a number")?;
+    let seconds = parse_seconds(&number)?;
+
+    match field.as_str() {
+        "duration" => Ok(Predicate::Duration(comparison, seconds)),
+        "start" => Ok(Predicate::Start(comparison, seconds)),
+        "end" => Ok(Predicate::End(comparison, seconds)),
+        _ => Err(unexpected(
+            "`label`, `duration`, `start` or `end`",
+            &Token::Word(field),
+        )),
+    }
+}
+
+/// Parses a number of seconds, which may end in `s` or `ms`.
+fn parse_seconds(number: &str) -> Result<f64> {
+    let (digits, scale) = number.strip_suffix("ms").map_or_else(
+        || (number.strip_suffix('s').unwrap_or(number), 1.0),
+        |digits| (digits, 0.001),
+    );
+
+    digits
+        .parse::<f64>()
+        .map(|value| value * scale)
+        .map_err(|_| unexpected("a number", &Token::Word(number.to_string())))
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_query {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        query::{Comparison, Context, Predicate, Query},
+        textgrid::TextGrid,
+    };
+
+    fn textgrid() -> TextGrid {
+        let tier = |name: &str, intervals: &[(f64, f64, &str)]| {
+            IntervalTier::new(
+                name.to_string(),
+                0.0,
+                1.0,
+                intervals
+                    .iter()
+                    .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                    .collect(),
+            )
+            .into()
+        };
+
+        let mut textgrid = TextGrid::new(0.0, 1.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            tier(
+                "words",
+                &[(0.0, 0.3, "the"), (0.3, 0.6, "man"), (0.6, 1.0, "the")],
+            ),
+            false,
+        );
+        textgrid.push_tier(
+            tier(
+                "phones",
+                &[
+                    (0.0, 0.1, "n"),
+                    (0.1, 0.3, "a"),
+                    (0.3, 0.4, "m"),
+                    (0.4, 0.6, "a"),
+                    (0.6, 0.7, "n"),
+                    (0.7, 0.73, "e"),
+                    (0.73, 1.0, "s"),
+                ],
+            ),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                1.0,
+                vec![Point::new(0.15, "H*".to_string())],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn builder() {
+        let query = Query::new("phones")
+            .matching(Predicate::label("^[aeiou]$").unwrap())
+            .matching(Predicate::Duration(Comparison::Greater, 0.05))
+            .with_context(Context::Previous, vec![Predicate::label("^[mn]$").unwrap()])
+            .with_context(
+                Context::Within("words".to_string()),
+                vec![Predicate::label_equals("the")],
+            );
+
+        let matches = query.run(&textgrid()).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].tier(), "phones");
+        assert_eq!(*matches[0].index(), 1);
+        assert_eq!((*matches[0].xmin(), *matches[0].xmax()), (0.1, 0.3));
+
+        let query = Query::new("words").with_context(
+            Context::Contains("tones".to_string()),
+            vec![Predicate::label("\\*").unwrap()],
+        );
+        assert_eq!(query.run(&textgrid()).unwrap()[0].text(), "the");
+    }
+
+    #[test]
+    fn parse() {
+        let textgrid = textgrid();
+
+        let matches = textgrid
+            .query(r#"phones where label ~ "^[aeiou]$" and duration > 50ms and prev label ~ "^[mn]$" and within words label = "the""#)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let matches = textgrid
+            .query("phones where label = a and next label = m")
+            .unwrap();
+        assert_eq!(*matches[0].index(), 1);
+
+        let matches = textgrid
+            .query("tones where overlaps phones label = a")
+            .unwrap();
+        assert_eq!(matches[0].text(), "H*");
+
+        assert_eq!(textgrid.query("phones").unwrap().len(), 7);
+        assert_eq!(
+            textgrid
+                .query("phones where start >= 0.6 and end <= 1s")
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let textgrid = textgrid();
+
+        assert_eq!(
+            textgrid
+                .query(r#"phones where label ~ "^\w$" and start < 0.2"#)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(textgrid
+            .query(r#"words where label ~ "^t\.e$""#)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            textgrid
+                .query(r#"tones where label ~ "\*" and label ~ "\"?H\\*""#)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let textgrid = textgrid();
+
+        assert!(textgrid.query("missing").is_err());
+        assert!(textgrid
+            .query("phones where within missing label = a")
+            .is_err());
+        assert!(textgrid.query("phones label = a").is_err());
+        assert!(textgrid.query("phones where label < a").is_err());
+        assert!(textgrid.query("phones where duration > short").is_err());
+        assert!(textgrid.query("phones where label ~ \"(\"").is_err());
+        assert!(textgrid.query("phones where label = \"a").is_err());
+        assert!(textgrid.query("phones where label =").is_err());
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    collections::HashSet,
+    io::{Error, ErrorKind, Result},
+};
+
+use getset::Getters;
+
+use crate::{
+    interval::Tier as IntervalTier,
+    point::Tier as PointTier,
+    textgrid::{TextGrid, Tier},
+    tier::AnnotationTier,
+    utilities::interval_boundaries,
+};
+
+/// A boundary or point moved, or left alone, by `TextGrid::snap_boundaries`.
+#[derive(Clone, Debug, Getters, PartialEq)]
+pub struct SnapMove {
+    /// The name of the tier the boundary or point is on.
+    #[getset(get = "pub")]
+    tier: String,
+    /// The time before snapping.
+    #[getset(get = "pub")]
+    from: f64,
+    /// The reference time it snaps to.
+    #[getset(get = "pub")]
+    to: f64,
+}
+
+impl SnapMove {
+    /// Returns how far the boundary or point moves.
+    #[must_use]
+    pub fn delta(&self) -> f64 {
+        self.to - self.from
+    }
+}
+
+/// The result of `TextGrid::snap_boundaries`.
+#[derive(Clone, Debug, Default, Getters, PartialEq)]
+pub struct SnapReport {
+    /// The boundaries and points that were moved, by tier and then by time.
+    #[getset(get = "pub")]
+    moved: Vec<SnapMove>,
+    /// The boundaries within the tolerance that were left alone, because moving them would
+    /// have left an interval with no duration or reversed the order of two boundaries.
+    #[getset(get = "pub")]
+    skipped: Vec<SnapMove>,
+}
+
+impl SnapReport {
+    /// Returns `true` if nothing was moved or skipped.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty() && self.skipped.is_empty()
+    }
+}
+
+impl TextGrid {
+    /// Moves the boundaries and points of every other tier that are within `tolerance` of a
+    /// boundary or point of the reference tier onto it, e.g. to line up phone ends with word
+    /// ends after rounding errors or manual edits.
+    ///
+    /// Each boundary moves to the nearest reference time. A boundary shared by two intervals
+    /// moves as one, so contiguous tiers stay contiguous, and the two sides of a small gap that
+    /// snap to the same time close it. A boundary is left alone if moving it would leave an
+    /// interval with no duration or carry it past a neighbouring boundary. The edges of a tier
+    /// are never moved. A point is left alone if another point is at its target already, or
+    /// snaps to it first.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance` - The furthest a boundary or point can be moved.
+    /// * `reference_tier` - The name of the tier whose boundaries or points stay put.
+    ///
+    /// # Returns
+    ///
+    /// A report of what was moved and what was left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference tier does not exist or `tolerance` is negative.
+    pub fn snap_boundaries(&mut self, tolerance: f64, reference_tier: &str) -> Result<SnapReport> {
+        if tolerance < 0.0 || tolerance.is_nan() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Tolerance must not be negative, got {tolerance}"),
+            ));
+        }
+
+        let reference = self.get_tier(reference_tier).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("Tier `{reference_tier}` does not exist"),
+            )
+        })?;
+        let mut targets = reference
+            .annotations()
+            .flat_map(|annotation| [annotation.xmin(), annotation.xmax()])
+            .collect::<Vec<f64>>();
+        targets.sort_by(f64::total_cmp);
+        targets.dedup();
+
+        let names = self
+            .tiers()
+            .iter()
+            .map(|tier| tier.name().to_string())
+            .filter(|name| name != reference_tier)
+            .collect::<Vec<String>>();
+
+        let mut report = SnapReport::default();
+        for name in names {
+            self.edit_tier(&name, |tier| match tier {
+                Tier::IntervalTier(interval_tier) => {
+                    snap_intervals(interval_tier, &targets, tolerance, &mut report);
+                }
+                Tier::PointTier(point_tier) => {
+                    snap_points(point_tier, &targets, tolerance, &mut report);
+                }
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+/// Finds the target nearest to `t`, if it is at most `tolerance` away and not `t` itself.
+#[allow(clippy::float_cmp)]
+fn nearest_target(targets: &[f64], t: f64, tolerance: f64) -> Option<f64> {
+    let index = targets.partition_point(|target| *target < t);
+    [index.checked_sub(1), Some(index)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| targets.get(i).copied())
+        .min_by(|a, b| (a - t).abs().total_cmp(&(b - t).abs()))
+        .filter(|target| (target - t).abs() <= tolerance && *target != t)
+}
+
+/// Snaps the inner boundaries of an interval tier, keeping every interval's duration positive.
+#[allow(clippy::float_cmp)]
+fn snap_intervals(
+    tier: &mut IntervalTier,
+    targets: &[f64],
+    tolerance: f64,
+    report: &mut SnapReport,
+) {
+    let (tier_xmin, tier_xmax) = (*tier.xmin(), *tier.xmax());
+    let boundaries = interval_boundaries(tier);
+    let mut snapped = boundaries.clone();
+
+    // Whether an interval ends, or starts, at each boundary. A boundary that only starts an
+    // interval has a gap before it, and one that only ends an interval has a gap after it.
+    let mut ends = vec![false; boundaries.len()];
+    let mut starts = vec![false; boundaries.len()];
+    let position = |time: f64| boundaries.partition_point(|boundary| *boundary < time);
+    for interval in tier.intervals() {
+        starts[position(*interval.xmin())] = true;
+        ends[position(*interval.xmax())] = true;
+    }
+
+    // Every boundary within the tolerance of a target moves first, so the two sides of a gap
+    // can close on the same target. Moves that leave a boundary out of order with a neighbour
+    // are then undone, rechecking the neighbours, until every boundary fits.
+    let proposed = boundaries
+        .iter()
+        .map(|&t| {
+            (t != tier_xmin && t != tier_xmax)
+                .then(|| nearest_target(targets, t, tolerance))
+                .flatten()
+        })
+        .collect::<Vec<Option<f64>>>();
+    for (k, target) in proposed.iter().enumerate() {
+        if let Some(target) = target {
+            snapped[k] = *target;
+        }
+    }
+
+    // The boundary must stay between its neighbours, and strictly so where an interval lies
+    // between them. Across a gap it may meet its neighbour, closing the gap.
+    let fits = |snapped: &[f64], k: usize| {
+        let t = snapped[k];
+        let before = k.checked_sub(1).map_or(tier_xmin, |before| snapped[before]);
+        let after = snapped.get(k + 1).copied().unwrap_or(tier_xmax);
+        (if ends[k] { before < t } else { before <= t })
+            && (if starts[k] { t < after } else { t <= after })
+    };
+    let mut pending = (0..boundaries.len())
+        .filter(|&k| proposed[k].is_some())
+        .collect::<Vec<usize>>();
+    while let Some(k) = pending.pop() {
+        if snapped[k] != boundaries[k] && !fits(&snapped, k) {
+            snapped[k] = boundaries[k];
+            pending.extend(
+                [k.checked_sub(1), Some(k + 1)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|&k| proposed.get(k).is_some_and(Option::is_some)),
+            );
+        }
+    }
+
+    for (k, target) in proposed.iter().enumerate() {
+        let Some(target) = target else {
+            continue;
+        };
+        let snap = SnapMove {
+            tier: tier.name().clone(),
+            from: boundaries[k],
+            to: *target,
+        };
+        if snapped[k] == *target {
+            report.moved.push(snap);
+        } else {
+            report.skipped.push(snap);
+        }
+    }
+
+    tier.edit_intervals(|intervals| {
+        for interval in intervals {
+            let (xmin, xmax) = (
+                snapped[position(*interval.xmin())],
+                snapped[position(*interval.xmax())],
+            );
+            interval.set_xmin(xmin);
+            interval.set_xmax(xmax);
+        }
+    });
+}
+
+/// Snaps the points of a point tier. A point is left alone if another point is already at its
+/// target, or has snapped to it first.
+fn snap_points(tier: &mut PointTier, targets: &[f64], tolerance: f64, report: &mut SnapReport) {
+    let name = tier.name().clone();
+    let mut taken = tier
+        .points()
+        .iter()
+        .map(|point| point.number().to_bits())
+        .collect::<HashSet<u64>>();
+
+    tier.edit_points(|points| {
+        for point in points {
+            let Some(target) = nearest_target(targets, *point.number(), tolerance) else {
+                continue;
+            };
+            let snap = SnapMove {
+                tier: name.clone(),
+                from: *point.number(),
+                to: target,
+            };
+            if taken.insert(target.to_bits()) {
+                point.set_number(target);
+                report.moved.push(snap);
+            } else {
+                report.skipped.push(snap);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_snap {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+        tier::TierIssue,
+    };
+
+    fn interval_tier(name: &str, intervals: &[(f64, f64, &str)]) -> IntervalTier {
+        IntervalTier::new(
+            name.to_string(),
+            0.0,
+            3.0,
+            intervals
+                .iter()
+                .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                .collect(),
+        )
+    }
+
+    fn textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            interval_tier("words", &[(0.0, 1.0, "daisy"), (1.0, 3.0, "bell")]).into(),
+            false,
+        );
+        textgrid.push_tier(
+            interval_tier(
+                "phones",
+                &[
+                    (0.0, 0.5, "d"),
+                    (0.5, 1.000_000_000_001, "ei"),
+                    (1.000_000_000_001, 1.003, "b"),
+                    (1.003, 2.0, "el"),
+                    (2.0, 3.0, ""),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Point::new(0.995, "H*".to_string()),
+                    Point::new(2.5, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn snap_boundaries() {
+        let mut textgrid = textgrid();
+        let report = textgrid.snap_boundaries(0.01, "words").unwrap();
+
+        let phones = textgrid.interval_tier("phones").unwrap();
+        assert_eq!(*phones.intervals()[1].xmax(), 1.0);
+        assert_eq!(*phones.intervals()[2].xmin(), 1.0);
+        assert_eq!(*phones.intervals()[2].xmax(), 1.003);
+        assert!(phones.check_overlaps(0.0).is_empty());
+
+        assert_eq!(
+            *textgrid.point_tier("tones").unwrap().points()[0].number(),
+            1.0
+        );
+
+        let moved = report
+            .moved()
+            .iter()
+            .map(|snap| (snap.tier().as_str(), *snap.to()))
+            .collect::<Vec<_>>();
+        assert_eq!(moved, vec![("phones", 1.0), ("tones", 1.0)]);
+        assert_eq!(report.skipped().len(), 1);
+        assert_eq!(*report.skipped()[0].from(), 1.003);
+    }
+
+    #[test]
+    fn gaps() {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            interval_tier("words", &[(0.0, 1.0, "daisy"), (1.0, 3.0, "bell")]).into(),
+            false,
+        );
+        textgrid.push_tier(
+            interval_tier("narrow", &[(0.0, 0.995, "d"), (0.997, 3.0, "b")]).into(),
+            false,
+        );
+        textgrid.push_tier(
+            interval_tier("wide", &[(0.0, 0.995, "d"), (1.005, 3.0, "b")]).into(),
+            false,
+        );
+        textgrid.push_tier(
+            interval_tier(
+                "crowded",
+                &[(0.0, 0.995, "d"), (0.997, 0.999, "x"), (0.999, 3.0, "b")],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Point::new(0.995, "H*".to_string()),
+                    Point::new(1.005, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        let report = textgrid.snap_boundaries(0.01, "words").unwrap();
+
+        for name in ["narrow", "wide"] {
+            let tier = textgrid.interval_tier(name).unwrap();
+            assert_eq!(*tier.intervals()[0].xmax(), 1.0);
+            assert_eq!(*tier.intervals()[1].xmin(), 1.0);
+            assert!(tier.check_overlaps(0.0).is_empty());
+        }
+
+        let crowded = textgrid.interval_tier("crowded").unwrap();
+        assert_eq!(*crowded.intervals()[0].xmax(), 0.995);
+        assert!(crowded
+            .check_overlaps(0.0)
+            .iter()
+            .all(|issue| matches!(issue, TierIssue::Gap { .. })));
+
+        let tones = textgrid.point_tier("tones").unwrap();
+        assert_eq!(*tones.points()[0].number(), 1.0);
+        assert_eq!(*tones.points()[1].number(), 1.005);
+        assert!(tones.check_overlaps(0.0).is_empty());
+
+        assert_eq!(report.moved().len(), 5);
+        assert_eq!(report.skipped().len(), 4);
+    }
+
+    #[test]
+    fn errors() {
+        let mut textgrid = textgrid();
+
+        assert!(textgrid.snap_boundaries(0.01, "missing").is_err());
+        assert!(textgrid.snap_boundaries(-0.01, "words").is_err());
+        assert!(textgrid.snap_boundaries(0.0, "words").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,578 @@
+use std::io::{Error, ErrorKind, Result};
+
+#[cfg(feature = "xml")]
+use roxmltree::{Document, ParsingOptions};
+
+use crate::{
+    input::Source,
+    point::{Point, Tier as PointTier},
+    textgrid::Tier,
+    utilities::{contiguous_tier, get_file_content, quote_csv},
+};
+
+/// The unit of the timestamps in a Sonic Visualiser layer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeFormat {
+    /// Timestamps and durations in seconds.
+    Seconds,
+    /// Timestamps and durations in audio frames at the given sample rate.
+    Frames(u32),
+}
+
+impl TimeFormat {
+    /// Converts a timestamp in this format to seconds.
+    fn to_seconds(self, time: f64) -> f64 {
+        match self {
+            Self::Seconds => time,
+            Self::Frames(sample_rate) => time / f64::from(sample_rate),
+        }
+    }
+
+    /// Formats a time in seconds in this format.
+    fn format(self, seconds: f64) -> String {
+        match self {
+            Self::Seconds => seconds.to_string(),
+            Self::Frames(sample_rate) => format!("{}", (seconds * f64::from(sample_rate)).round()),
+        }
+    }
+}
+
+/// The kind of Sonic Visualiser layer a CSV export holds.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LayerKind {
+    /// Decide from the rows. The layer holds regions if any row is `time,duration,label`, or
+    /// if every row is `time,number`, which is how a region layer with no labels is exported.
+    #[default]
+    Auto,
+    /// Rows of `time,label`.
+    Instants,
+    /// Rows of `time,duration,label`, or `time,duration` for regions with no label.
+    Regions,
+}
+
+/// Parses a Sonic Visualiser layer exported as CSV.
+///
+/// Rows of `time,label` form an instant layer, which becomes a `PointTier`. Rows of
+/// `time,duration,label` form a region layer, which becomes an `IntervalTier` with gaps
+/// filled by empty intervals, and rows of `time,duration` in a region layer are regions with
+/// no label. Labels may be quoted, and rows may be separated by commas or tabs.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+/// * `name` - The name of the resulting tier.
+/// * `time_format` - The unit of the timestamps and durations.
+/// * `kind` - The kind of layer. `LayerKind::Auto` reads an instant layer whose labels are all
+///   numbers, such as beat numbers, as regions, so pass `LayerKind::Instants` for those.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, a timestamp cannot be parsed as a float, or
+/// the file mixes instants and regions.
+pub fn parse_sv_csv<I: Into<Source>>(
+    input: I,
+    name: &str,
+    time_format: TimeFormat,
+    kind: LayerKind,
+) -> Result<Tier> {
+    let (content, _) = get_file_content(input.into())?;
+
+    let mut rows = Vec::<(usize, f64, char, Vec<&str>)>::new();
+    for (line_number, line) in content.iter().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let separator = if line.contains('\t') { '\t' } else { ',' };
+        let fields = line.splitn(3, separator).collect::<Vec<&str>>();
+
+        let time = fields[0].trim().parse::<f64>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "CSV malformed; could not parse time \"{}\" on line {} as a float",
+                    fields[0],
+                    line_number + 1
+                ),
+            )
+        })?;
+        rows.push((line_number, time_format.to_seconds(time), separator, fields));
+    }
+
+    // A region layer has a duration on every row, and a row with no label has no third field
+    let duration = |fields: &[&str]| {
+        fields
+            .get(1)
+            .and_then(|duration| duration.trim().parse::<f64>().ok())
+    };
+    let is_region_layer = match kind {
+        LayerKind::Auto => {
+            rows.iter()
+                .any(|(_, _, _, fields)| fields.len() == 3 && duration(fields).is_some())
+                || !rows.is_empty()
+                    && rows
+                        .iter()
+                        .all(|(_, _, _, fields)| fields.len() == 2 && duration(fields).is_some())
+        }
+        LayerKind::Instants => false,
+        LayerKind::Regions => true,
+    };
+
+    let mut points = Vec::<Point>::new();
+    let mut regions = Vec::<(f64, f64, String)>::new();
+    for (line_number, time, separator, fields) in rows {
+        if !is_region_layer {
+            points.push(Point::new(
+                time,
+                unquote_csv(&fields[1..].join(&separator.to_string())),
+            ));
+            continue;
+        }
+        let Some(duration) = duration(&fields) else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "CSV malformed; line {} has no duration, but other lines are regions",
+                    line_number + 1
+                ),
+            ));
+        };
+        regions.push((
+            time,
+            time + time_format.to_seconds(duration),
+            fields
+                .get(2)
+                .map(|label| unquote_csv(label))
+                .unwrap_or_default(),
+        ));
+    }
+
+    Ok(build_tier(name, points, regions))
+}
+
+/// Formats an interval or point tier as a Sonic Visualiser CSV layer.
+///
+/// Interval tiers become region layers of `time,duration,label` rows, skipping intervals with
+/// empty or whitespace-only text. Point tiers become instant layers of `time,label` rows.
+///
+/// # Arguments
+///
+/// * `tier` - The tier to format.
+/// * `time_format` - The unit to write timestamps and durations in.
+///
+/// # Returns
+///
+/// A vector of strings containing one CSV row per region or instant.
+#[must_use]
+pub fn format_sv_csv(tier: &Tier, time_format: TimeFormat) -> Vec<String> {
+    match tier {
+        Tier::IntervalTier(interval_tier) => interval_tier
+            .intervals()
+            .iter()
+            .filter(|interval| !interval.text().trim().is_empty())
+            .map(|interval| {
+                format!(
+                    "{},{},{}",
+                    time_format.format(*interval.xmin()),
+                    time_format.format(interval.get_duration()),
+                    quote_csv(interval.text())
+                )
+            })
+            .collect(),
+        Tier::PointTier(point_tier) => point_tier
+            .points()
+            .iter()
+            .map(|point| {
+                format!(
+                    "{},{}",
+                    time_format.format(*point.number()),
+                    quote_csv(point.mark())
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Parses a Sonic Visualiser `.svl` layer file.
+///
+/// Layers whose points have a `duration` (regions and notes) become an `IntervalTier`, with
+/// gaps filled by empty intervals; all others (time instants) become a `PointTier`. The tier
+/// is named after the layer, falling back to the model's name.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, is not well-formed XML, has no model with a
+/// `sampleRate`, or has a point whose `frame` or `duration` cannot be parsed.
+#[cfg(feature = "xml")]
+pub fn parse_svl<I: Into<Source>>(input: I) -> Result<Tier> {
+    let (content, _) = get_file_content(input.into())?;
+    let content = content.join("\n");
+
+    let malformed =
+        |message: String| Error::new(ErrorKind::InvalidData, format!("SVL malformed; {message}"));
+
+    // Sonic Visualiser writes a `<!DOCTYPE sonic-visualiser>` declaration
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(&content, options)
+        .map_err(|error| malformed(error.to_string()))?;
+
+    let model = document
+        .descendants()
+        .find(|node| node.has_tag_name("model"))
+        .ok_or_else(|| malformed("missing `model`".to_string()))?;
+    let sample_rate = model
+        .attribute("sampleRate")
+        .and_then(|sample_rate| sample_rate.parse::<u32>().ok())
+        .ok_or_else(|| malformed("missing or invalid `sampleRate`".to_string()))?;
+    let time_format = TimeFormat::Frames(sample_rate);
+
+    let name = document
+        .descendants()
+        .find(|node| node.has_tag_name("layer"))
+        .and_then(|layer| layer.attribute("name"))
+        .or_else(|| model.attribute("name"))
+        .unwrap_or_default();
+
+    let mut points = Vec::<Point>::new();
+    let mut regions = Vec::<(f64, f64, String)>::new();
+
+    for point in document
+        .descendants()
+        .filter(|node| node.has_tag_name("point"))
+    {
+        let parse = |attribute: &str| {
+            point
+                .attribute(attribute)
+                .map(|value| {
+                    value.parse::<f64>().map_err(|_| {
+                        malformed(format!("could not parse `{attribute}` \"{value}\""))
+                    })
+                })
+                .transpose()
+        };
+
+        let frame =
+            parse("frame")?.ok_or_else(|| malformed("point is missing `frame`".to_string()))?;
+        let time = time_format.to_seconds(frame);
+        let label = point.attribute("label").unwrap_or_default().to_string();
+
+        match parse("duration")? {
+            Some(duration) => regions.push((time, time + time_format.to_seconds(duration), label)),
+            None => points.push(Point::new(time, label)),
+        }
+    }
+
+    Ok(build_tier(name, points, regions))
+}
+
+/// Formats an interval or point tier as a Sonic Visualiser `.svl` layer file.
+///
+/// Interval tiers become region layers, skipping intervals with empty or whitespace-only
+/// text. Point tiers become time instant layers.
+///
+/// # Arguments
+///
+/// * `tier` - The tier to format.
+/// * `sample_rate` - The sample rate of the audio the layer will be overlaid on.
+///
+/// # Returns
+///
+/// A vector of strings containing the lines of the `.svl` file.
+#[must_use]
+pub fn format_svl(tier: &Tier, sample_rate: u32) -> Vec<String> {
+    let time_format = TimeFormat::Frames(sample_rate);
+
+    let (name, end, rows, is_region) = match tier {
+        Tier::IntervalTier(interval_tier) => (
+            interval_tier.name(),
+            *interval_tier.xmax(),
+            interval_tier
+                .intervals()
+                .iter()
+                .filter(|interval| !interval.text().trim().is_empty())
+                .map(|interval| {
+                    format!(
+                        "      <point frame=\"{}\" value=\"0\" duration=\"{}\" label=\"{}\" />",
+                        time_format.format(*interval.xmin()),
+                        time_format.format(interval.get_duration()),
+                        escape_xml(interval.text())
+                    )
+                })
+                .collect::<Vec<String>>(),
+            true,
+        ),
+        Tier::PointTier(point_tier) => (
+            point_tier.name(),
+            *point_tier.xmax(),
+            point_tier
+                .points()
+                .iter()
+                .map(|point| {
+                    format!(
+                        "      <point frame=\"{}\" label=\"{}\" />",
+                        time_format.format(*point.number()),
+                        escape_xml(point.mark())
+                    )
+                })
+                .collect::<Vec<String>>(),
+            false,
+        ),
+    };
+
+    let (dimensions, model_extra, layer_type) = if is_region {
+        (
+            3,
+            " subtype=\"region\" valueQuantization=\"0\" minimum=\"0\" maximum=\"0\" units=\"\"",
+            "regions",
+        )
+    } else {
+        (1, "", "timeinstants")
+    };
+
+    let mut out_strings: Vec<String> = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".into(),
+        "<!DOCTYPE sonic-visualiser>".into(),
+        "<sv>".into(),
+        "  <data>".into(),
+        format!(
+            "    <model id=\"1\" name=\"{}\" sampleRate=\"{sample_rate}\" start=\"0\" end=\"{}\" type=\"sparse\" dimensions=\"{dimensions}\" resolution=\"1\" notifyOnAdd=\"true\" dataset=\"0\"{model_extra} />",
+            escape_xml(name),
+            time_format.format(end)
+        ),
+        format!("    <dataset id=\"0\" dimensions=\"{dimensions}\">"),
+    ];
+    out_strings.extend(rows);
+    out_strings.extend([
+        "    </dataset>".into(),
+        "  </data>".into(),
+        "  <display>".into(),
+        format!(
+            "    <layer id=\"2\" type=\"{layer_type}\" name=\"{}\" model=\"1\" />",
+            escape_xml(name)
+        ),
+        "  </display>".into(),
+        "</sv>".into(),
+    ]);
+
+    out_strings
+}
+
+/// Builds a point tier from `points`, or an interval tier from `regions` if there are any.
+fn build_tier(name: &str, points: Vec<Point>, regions: Vec<(f64, f64, String)>) -> Tier {
+    if regions.is_empty() {
+        let xmax = points
+            .iter()
+            .map(|point| *point.number())
+            .fold(0.0, f64::max);
+        let mut point_tier = PointTier::new(name.to_string(), 0.0, xmax, Vec::new());
+        point_tier.push_points(points, false);
+
+        Tier::PointTier(point_tier)
+    } else {
+        let xmax = regions.iter().map(|(_, end, _)| *end).fold(0.0, f64::max);
+
+        Tier::IntervalTier(contiguous_tier(name, xmax, regions))
+    }
+}
+
+/// Removes surrounding quotes from a CSV field and unescapes doubled quotes.
+fn unquote_csv(field: &str) -> String {
+    let field = field.trim();
+    if field.len() > 1 && field.starts_with('"') && field.ends_with('"') {
+        field[1..field.len() - 1].replace("\"\"", "\"")
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes the characters that cannot appear in an XML attribute value.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_sonic_visualiser {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        sonic_visualiser::{format_sv_csv, format_svl, parse_sv_csv, LayerKind, TimeFormat},
+        textgrid::Tier,
+    };
+
+    #[test]
+    fn parse_regions() {
+        let tier = parse_sv_csv(
+            "0.5,0.25,daisy\n1.0,0.5,\"bell, bell\"\n",
+            "words",
+            TimeFormat::Seconds,
+            LayerKind::Auto,
+        )
+        .unwrap();
+
+        let Tier::IntervalTier(tier) = tier else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.name(), "words");
+        assert_eq!(tier.get_size(), 4);
+        assert_eq!(tier.intervals()[1].text(), "daisy");
+        assert_eq!(tier.intervals()[3].text(), "bell, bell");
+        assert_eq!(tier.xmax(), &1.5);
+    }
+
+    #[test]
+    fn parse_instants_in_frames() {
+        let tier = parse_sv_csv(
+            "22050\tH*\n44100\tL%\n",
+            "tones",
+            TimeFormat::Frames(44100),
+            LayerKind::Auto,
+        )
+        .unwrap();
+
+        let Tier::PointTier(tier) = tier else {
+            panic!("Expected PointTier, got IntervalTier");
+        };
+        assert_eq!(tier.get_size(), 2);
+        assert_eq!(tier.points()[0].number(), &0.5);
+        assert_eq!(tier.points()[1].mark(), "L%");
+    }
+
+    #[test]
+    fn parse_unlabelled_regions() {
+        let tier = parse_sv_csv(
+            "0.5,0.25\n1.0,0.5,bell\n",
+            "words",
+            TimeFormat::Seconds,
+            LayerKind::Auto,
+        )
+        .unwrap();
+
+        let Tier::IntervalTier(tier) = tier else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.get_size(), 4);
+        assert_eq!(tier.intervals()[1].xmax(), &0.75);
+        assert_eq!(tier.intervals()[1].text(), "");
+        assert_eq!(tier.intervals()[3].text(), "bell");
+    }
+
+    #[test]
+    fn parse_all_unlabelled_regions() {
+        let content = "0.5,0.25\n1.0,0.5\n";
+
+        let Tier::IntervalTier(tier) =
+            parse_sv_csv(content, "words", TimeFormat::Seconds, LayerKind::Auto).unwrap()
+        else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(tier.get_size(), 4);
+        assert_eq!(tier.intervals()[3].xmax(), &1.5);
+        assert!(tier
+            .intervals()
+            .iter()
+            .all(|interval| interval.text().is_empty()));
+
+        let Tier::PointTier(tier) =
+            parse_sv_csv(content, "beats", TimeFormat::Seconds, LayerKind::Instants).unwrap()
+        else {
+            panic!("Expected PointTier, got IntervalTier");
+        };
+        assert_eq!(tier.points()[1].mark(), "0.5");
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_sv_csv(
+            "half,daisy\n",
+            "words",
+            TimeFormat::Seconds,
+            LayerKind::Auto
+        )
+        .is_err());
+        assert!(
+            parse_sv_csv("0.5,H*\n", "words", TimeFormat::Seconds, LayerKind::Regions).is_err()
+        );
+        assert!(parse_sv_csv(
+            "0.5,H*\n1.0,0.5,bell\n",
+            "words",
+            TimeFormat::Seconds,
+            LayerKind::Auto
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn format_csv() {
+        let tier = Tier::IntervalTier(IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            1.0,
+            vec![
+                Interval::new(0.0, 0.5, String::new()),
+                Interval::new(0.5, 1.0, "bell".to_string()),
+            ],
+        ));
+
+        assert_eq!(
+            format_sv_csv(&tier, TimeFormat::Seconds),
+            vec!["0.5,0.5,bell"]
+        );
+        assert_eq!(
+            format_sv_csv(&tier, TimeFormat::Frames(100)),
+            vec!["50,50,bell"]
+        );
+    }
+
+    #[test]
+    fn format_instants() {
+        let tier = Tier::PointTier(PointTier::new(
+            "tones".to_string(),
+            0.0,
+            1.0,
+            vec![Point::new(0.5, "H*".to_string())],
+        ));
+
+        let svl = format_svl(&tier, 100);
+
+        assert!(svl.contains(&"      <point frame=\"50\" label=\"H*\" />".to_string()));
+        assert!(svl
+            .iter()
+            .any(|line| line.contains("type=\"timeinstants\"")));
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn svl_round_trip() {
+        use crate::sonic_visualiser::parse_svl;
+
+        let tier = Tier::IntervalTier(IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            1.0,
+            vec![
+                Interval::new(0.0, 0.5, "daisy & co".to_string()),
+                Interval::new(0.5, 1.0, "bell".to_string()),
+            ],
+        ));
+
+        let Tier::IntervalTier(parsed) = parse_svl(format_svl(&tier, 100)).unwrap() else {
+            panic!("Expected IntervalTier, got PointTier");
+        };
+        assert_eq!(parsed.name(), "words");
+        assert_eq!(parsed.get_size(), 2);
+        assert_eq!(parsed.intervals()[0].text(), "daisy & co");
+        assert_eq!(parsed.intervals()[1].xmin(), &0.5);
+    }
+}
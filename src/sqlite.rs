@@ -0,0 +1,562 @@
+use std::io::{Error, ErrorKind, Result};
+
+use rusqlite::{params, Connection};
+
+use crate::{
+    interval::{Interval, Tier as IntervalTier},
+    point::{Point, Tier as PointTier},
+    textgrid::{TextGrid, Tier},
+};
+
+/// The corpus schema. Times are stored as `REAL`, which holds an `f64` exactly, and every
+/// row keeps its position so that tier, interval and point order survive a round trip.
+///
+/// A B-tree index on `xmin` cannot also bound `xmax`, so each interval's tier and extent are
+/// kept in the `intervals_span` R*Tree as well, with the tier id as a zero-width dimension.
+/// The R*Tree stores 32-bit bounds rounded outwards, which makes it a filter only: the exact
+/// tier ids and times are still compared on `intervals`.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS grids (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        xmin REAL NOT NULL,
+        xmax REAL NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS tiers (
+        id INTEGER PRIMARY KEY,
+        grid_id INTEGER NOT NULL REFERENCES grids(id),
+        position INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        kind TEXT NOT NULL CHECK (kind IN ('interval', 'point')),
+        xmin REAL NOT NULL,
+        xmax REAL NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS intervals (
+        id INTEGER PRIMARY KEY,
+        tier_id INTEGER NOT NULL REFERENCES tiers(id),
+        position INTEGER NOT NULL,
+        xmin REAL NOT NULL,
+        xmax REAL NOT NULL,
+        text TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS points (
+        id INTEGER PRIMARY KEY,
+        tier_id INTEGER NOT NULL REFERENCES tiers(id),
+        position INTEGER NOT NULL,
+        number REAL NOT NULL,
+        mark TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS tiers_grid ON tiers (grid_id, position);
+    CREATE INDEX IF NOT EXISTS tiers_name ON tiers (name);
+    CREATE INDEX IF NOT EXISTS intervals_tier ON intervals (tier_id, position);
+    CREATE INDEX IF NOT EXISTS points_time ON points (tier_id, number);
+    CREATE VIRTUAL TABLE IF NOT EXISTS intervals_span USING rtree (id, tier_min, tier_max, xmin, xmax);
+";
+
+/// Creates the corpus tables and indices if they do not exist yet.
+///
+/// # Arguments
+///
+/// * `connection` - The database connection.
+///
+/// # Errors
+///
+/// Returns an error if the schema cannot be created.
+pub fn create_schema(connection: &Connection) -> Result<()> {
+    connection.execute_batch(SCHEMA).map_err(sqlite_error)
+}
+
+/// Lists the names of all `TextGrid`s in the database, in alphabetical order.
+///
+/// # Arguments
+///
+/// * `connection` - The database connection.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried.
+pub fn list_grids(connection: &Connection) -> Result<Vec<String>> {
+    if !has_schema(connection)? {
+        return Ok(Vec::new());
+    }
+
+    let mut statement = connection
+        .prepare("SELECT name FROM grids ORDER BY name")
+        .map_err(sqlite_error)?;
+    let names = statement
+        .query_map([], |row| row.get(0))
+        .map_err(sqlite_error)?
+        .collect::<rusqlite::Result<Vec<String>>>()
+        .map_err(sqlite_error)?;
+
+    Ok(names)
+}
+
+/// Finds all intervals overlapping `[t0, t1]` on tiers with the given name, across the whole
+/// database. Intervals that only touch the range at one of its ends are not included.
+///
+/// # Arguments
+///
+/// * `connection` - The database connection.
+/// * `tier_name` - The name of the interval tiers to search.
+/// * `t0` - The start of the time range.
+/// * `t1` - The end of the time range.
+///
+/// # Returns
+///
+/// The name of each matching interval's `TextGrid` together with the interval, ordered by
+/// `TextGrid` name and time.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried.
+pub fn query_intervals(
+    connection: &Connection,
+    tier_name: &str,
+    t0: f64,
+    t1: f64,
+) -> Result<Vec<(String, Interval)>> {
+    if !has_schema(connection)? {
+        return Ok(Vec::new());
+    }
+
+    // `CROSS JOIN` fixes the join order, so that the named tiers are found first and then
+    // searched in the R*Tree, rather than going through `intervals` by tier
+    let mut statement = connection
+        .prepare(
+            "SELECT grids.name, intervals.xmin, intervals.xmax, intervals.text
+             FROM tiers
+             CROSS JOIN intervals_span
+                 ON intervals_span.tier_min <= tiers.id AND intervals_span.tier_max >= tiers.id
+                 AND intervals_span.xmin < ?3 AND intervals_span.xmax > ?2
+             CROSS JOIN intervals
+                 ON intervals.id = intervals_span.id AND intervals.tier_id = tiers.id
+             JOIN grids ON grids.id = tiers.grid_id
+             WHERE tiers.name = ?1 AND tiers.kind = 'interval'
+                 AND intervals.xmin < ?3 AND intervals.xmax > ?2
+             ORDER BY grids.name, intervals.xmin",
+        )
+        .map_err(sqlite_error)?;
+    let intervals = statement
+        .query_map(params![tier_name, t0, t1], |row| {
+            Ok((
+                row.get(0)?,
+                Interval::new(row.get(1)?, row.get(2)?, row.get(3)?),
+            ))
+        })
+        .map_err(sqlite_error)?
+        .collect::<rusqlite::Result<Vec<(String, Interval)>>>()
+        .map_err(sqlite_error)?;
+
+    Ok(intervals)
+}
+
+/// Finds all points within `[t0, t1]` on tiers with the given name, across the whole database.
+///
+/// # Arguments
+///
+/// * `connection` - The database connection.
+/// * `tier_name` - The name of the point tiers to search.
+/// * `t0` - The start of the time range.
+/// * `t1` - The end of the time range.
+///
+/// # Returns
+///
+/// The name of each matching point's `TextGrid` together with the point, ordered by
+/// `TextGrid` name and time.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be queried.
+pub fn query_points(
+    connection: &Connection,
+    tier_name: &str,
+    t0: f64,
+    t1: f64,
+) -> Result<Vec<(String, Point)>> {
+    if !has_schema(connection)? {
+        return Ok(Vec::new());
+    }
+
+    let mut statement = connection
+        .prepare(
+            "SELECT grids.name, points.number, points.mark
+             FROM points
+             JOIN tiers ON tiers.id = points.tier_id
+             JOIN grids ON grids.id = tiers.grid_id
+             WHERE tiers.name = ?1 AND tiers.kind = 'point'
+                 AND points.number >= ?2 AND points.number <= ?3
+             ORDER BY grids.name, points.number",
+        )
+        .map_err(sqlite_error)?;
+    let points = statement
+        .query_map(params![tier_name, t0, t1], |row| {
+            Ok((row.get(0)?, Point::new(row.get(1)?, row.get(2)?)))
+        })
+        .map_err(sqlite_error)?
+        .collect::<rusqlite::Result<Vec<(String, Point)>>>()
+        .map_err(sqlite_error)?;
+
+    Ok(points)
+}
+
+impl TextGrid {
+    /// Saves the `TextGrid` to a corpus database under its name, replacing any `TextGrid`
+    /// already saved under that name. The schema is created if it does not exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - The database connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `TextGrid` cannot be written. Nothing is written in that case.
+    pub fn save_to_db(&self, connection: &Connection) -> Result<()> {
+        create_schema(connection)?;
+
+        let transaction = connection.unchecked_transaction().map_err(sqlite_error)?;
+
+        delete_grid(&transaction, self.name())?;
+
+        transaction
+            .execute(
+                "INSERT INTO grids (name, xmin, xmax) VALUES (?1, ?2, ?3)",
+                params![self.name(), self.xmin(), self.xmax()],
+            )
+            .map_err(sqlite_error)?;
+        let grid_id = transaction.last_insert_rowid();
+
+        for (position, tier) in (0_i64..).zip(self.tiers()) {
+            let (name, kind, xmin, xmax) = match tier {
+                Tier::IntervalTier(interval_tier) => (
+                    interval_tier.name(),
+                    "interval",
+                    interval_tier.xmin(),
+                    interval_tier.xmax(),
+                ),
+                Tier::PointTier(point_tier) => (
+                    point_tier.name(),
+                    "point",
+                    point_tier.xmin(),
+                    point_tier.xmax(),
+                ),
+            };
+            transaction
+                .execute(
+                    "INSERT INTO tiers (grid_id, position, name, kind, xmin, xmax)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![grid_id, position, name, kind, xmin, xmax],
+                )
+                .map_err(sqlite_error)?;
+            let tier_id = transaction.last_insert_rowid();
+
+            match tier {
+                Tier::IntervalTier(interval_tier) => {
+                    let mut statement = transaction
+                        .prepare(
+                            "INSERT INTO intervals (tier_id, position, xmin, xmax, text)
+                             VALUES (?1, ?2, ?3, ?4, ?5)",
+                        )
+                        .map_err(sqlite_error)?;
+                    let mut span_statement = transaction
+                        .prepare(
+                            "INSERT INTO intervals_span (id, tier_min, tier_max, xmin, xmax)
+                             VALUES (?1, ?2, ?2, ?3, ?4)",
+                        )
+                        .map_err(sqlite_error)?;
+                    for (position, interval) in (0_i64..).zip(interval_tier.intervals()) {
+                        statement
+                            .execute(params![
+                                tier_id,
+                                position,
+                                interval.xmin(),
+                                interval.xmax(),
+                                interval.text()
+                            ])
+                            .map_err(sqlite_error)?;
+                        span_statement
+                            .execute(params![
+                                transaction.last_insert_rowid(),
+                                tier_id,
+                                interval.xmin(),
+                                interval.xmax()
+                            ])
+                            .map_err(sqlite_error)?;
+                    }
+                }
+                Tier::PointTier(point_tier) => {
+                    let mut statement = transaction
+                        .prepare(
+                            "INSERT INTO points (tier_id, position, number, mark)
+                             VALUES (?1, ?2, ?3, ?4)",
+                        )
+                        .map_err(sqlite_error)?;
+                    for (position, point) in (0_i64..).zip(point_tier.points()) {
+                        statement
+                            .execute(params![tier_id, position, point.number(), point.mark()])
+                            .map_err(sqlite_error)?;
+                    }
+                }
+            }
+        }
+
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    /// Loads a `TextGrid` from a corpus database by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - The database connection.
+    /// * `name` - The name the `TextGrid` was saved under.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read, or `ErrorKind::NotFound` if there is no
+    /// `TextGrid` with that name.
+    pub fn load_from_db(connection: &Connection, name: &str) -> Result<Self> {
+        let not_found = || {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("TextGrid `{name}` does not exist in the database"),
+            )
+        };
+        if !has_schema(connection)? {
+            return Err(not_found());
+        }
+
+        let (grid_id, xmin, xmax) = connection
+            .query_row(
+                "SELECT id, xmin, xmax FROM grids WHERE name = ?1",
+                params![name],
+                |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|error| match error {
+                rusqlite::Error::QueryReturnedNoRows => not_found(),
+                error => sqlite_error(error),
+            })?;
+
+        let mut tier_statement = connection
+            .prepare(
+                "SELECT id, name, kind, xmin, xmax FROM tiers WHERE grid_id = ?1 ORDER BY position",
+            )
+            .map_err(sqlite_error)?;
+        let tier_rows = tier_statement
+            .query_map(params![grid_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, f64>(4)?,
+                ))
+            })
+            .map_err(sqlite_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sqlite_error)?;
+
+        let mut interval_statement = connection
+            .prepare("SELECT xmin, xmax, text FROM intervals WHERE tier_id = ?1 ORDER BY position")
+            .map_err(sqlite_error)?;
+        let mut point_statement = connection
+            .prepare("SELECT number, mark FROM points WHERE tier_id = ?1 ORDER BY position")
+            .map_err(sqlite_error)?;
+
+        let mut tiers = Vec::<Tier>::new();
+        for (tier_id, tier_name, kind, tier_xmin, tier_xmax) in tier_rows {
+            let tier = if kind == "interval" {
+                let intervals = interval_statement
+                    .query_map(params![tier_id], |row| {
+                        Ok(Interval::new(row.get(0)?, row.get(1)?, row.get(2)?))
+                    })
+                    .map_err(sqlite_error)?
+                    .collect::<rusqlite::Result<Vec<Interval>>>()
+                    .map_err(sqlite_error)?;
+
+                Tier::IntervalTier(IntervalTier::new(
+                    tier_name, tier_xmin, tier_xmax, intervals,
+                ))
+            } else {
+                let points = point_statement
+                    .query_map(params![tier_id], |row| {
+                        Ok(Point::new(row.get(0)?, row.get(1)?))
+                    })
+                    .map_err(sqlite_error)?
+                    .collect::<rusqlite::Result<Vec<Point>>>()
+                    .map_err(sqlite_error)?;
+
+                Tier::PointTier(PointTier::new(tier_name, tier_xmin, tier_xmax, points))
+            };
+            tiers.push(tier);
+        }
+
+        Ok(Self::new(xmin, xmax, tiers, name.to_string()))
+    }
+}
+
+/// Checks whether the corpus tables exist, without creating them, so that reading works on a
+/// read-only connection to a database nothing has been saved to.
+fn has_schema(connection: &Connection) -> Result<bool> {
+    connection
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'grids')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(sqlite_error)
+}
+
+/// Deletes a `TextGrid` and all of its tiers, intervals and points.
+fn delete_grid(connection: &Connection, name: &str) -> Result<()> {
+    for statement in [
+        "DELETE FROM intervals_span WHERE id IN
+             (SELECT intervals.id FROM intervals
+              JOIN tiers ON tiers.id = intervals.tier_id
+              JOIN grids ON grids.id = tiers.grid_id WHERE grids.name = ?1)",
+        "DELETE FROM intervals WHERE tier_id IN
+             (SELECT tiers.id FROM tiers JOIN grids ON grids.id = tiers.grid_id WHERE grids.name = ?1)",
+        "DELETE FROM points WHERE tier_id IN
+             (SELECT tiers.id FROM tiers JOIN grids ON grids.id = tiers.grid_id WHERE grids.name = ?1)",
+        "DELETE FROM tiers WHERE grid_id IN (SELECT id FROM grids WHERE name = ?1)",
+        "DELETE FROM grids WHERE name = ?1",
+    ] {
+        connection
+            .execute(statement, params![name])
+            .map_err(sqlite_error)?;
+    }
+
+    Ok(())
+}
+
+/// Converts a `rusqlite::Error` into an `std::io::Error`.
+fn sqlite_error(error: rusqlite::Error) -> Error {
+    Error::new(ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_sqlite {
+    use rusqlite::{Connection, OpenFlags};
+
+    use crate::{
+        parse_textgrid,
+        sqlite::{list_grids, query_intervals, query_points},
+        textgrid::{OutputFormat, TextGrid},
+    };
+
+    #[test]
+    fn round_trip() {
+        let connection = Connection::open_in_memory().unwrap();
+        let textgrid = parse_textgrid("example/long.TextGrid", false).unwrap();
+
+        textgrid.save_to_db(&connection).unwrap();
+        let loaded = TextGrid::load_from_db(&connection, "long.TextGrid").unwrap();
+
+        assert_eq!(loaded.xmax(), textgrid.xmax());
+        assert_eq!(loaded.get_size(), textgrid.get_size());
+
+        let directory = std::env::temp_dir().join("textgridde-rs-test-sqlite");
+        textgrid
+            .write(directory.join("original.TextGrid"), OutputFormat::Long)
+            .unwrap();
+        loaded
+            .write(directory.join("loaded.TextGrid"), OutputFormat::Long)
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(directory.join("original.TextGrid")).unwrap(),
+            std::fs::read_to_string(directory.join("loaded.TextGrid")).unwrap()
+        );
+    }
+
+    #[test]
+    fn save_replaces() {
+        let connection = Connection::open_in_memory().unwrap();
+        let textgrid = parse_textgrid("example/long.TextGrid", false).unwrap();
+
+        textgrid.save_to_db(&connection).unwrap();
+        textgrid.save_to_db(&connection).unwrap();
+
+        assert_eq!(list_grids(&connection).unwrap(), vec!["long.TextGrid"]);
+        assert_eq!(
+            query_intervals(&connection, "John", 0.0, 1.0)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let spans: i64 = connection
+            .query_row("SELECT COUNT(*) FROM intervals_span", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(spans, 2);
+    }
+
+    #[test]
+    fn queries() {
+        let connection = Connection::open_in_memory().unwrap();
+        parse_textgrid("example/long.TextGrid", false)
+            .unwrap()
+            .save_to_db(&connection)
+            .unwrap();
+
+        let intervals = query_intervals(&connection, "John", 1.0, 2.0).unwrap();
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].1.text(), "daisy bell");
+
+        assert!(query_intervals(&connection, "John", 2.3, 3.0)
+            .unwrap()
+            .is_empty());
+
+        // Closer to 2.3 than the R*Tree's 32-bit bounds can tell apart
+        assert_eq!(
+            query_intervals(&connection, "John", 2.3 - 1e-12, 3.0)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(query_intervals(&connection, "John", 2.3 + 1e-12, 3.0)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            query_points(&connection, "Bell", 0.5, 1.5).unwrap().len(),
+            1
+        );
+        assert!(query_points(&connection, "Bell", 1.5, 2.0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn load_missing() {
+        let connection = Connection::open_in_memory().unwrap();
+
+        let error = TextGrid::load_from_db(&connection, "missing").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn read_only() {
+        let directory = std::env::temp_dir().join("textgridde-rs-test-sqlite-read-only");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("corpus.db");
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap();
+
+        let read_only = || Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY);
+        let connection = read_only().unwrap();
+        assert!(list_grids(&connection).unwrap().is_empty());
+        assert!(query_points(&connection, "Bell", 0.0, 1.0)
+            .unwrap()
+            .is_empty());
+        let error = TextGrid::load_from_db(&connection, "long.TextGrid").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+        parse_textgrid("example/long.TextGrid", false)
+            .unwrap()
+            .save_to_db(&Connection::open(&path).unwrap())
+            .unwrap();
+        let connection = read_only().unwrap();
+        assert_eq!(list_grids(&connection).unwrap(), vec!["long.TextGrid"]);
+        assert!(TextGrid::load_from_db(&connection, "long.TextGrid").is_ok());
+    }
+}
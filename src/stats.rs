@@ -0,0 +1,600 @@
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use getset::{Getters, Setters};
+
+use crate::{
+    textgrid::{TextGrid, Tier},
+    tier::AnnotationTier,
+    utilities::quote_csv,
+};
+
+/// The percentiles included in `Stats::to_csv`.
+const CSV_PERCENTILES: [f64; 4] = [10.0, 25.0, 75.0, 90.0];
+
+/// Descriptive statistics of a set of durations, in seconds.
+#[derive(Clone, Debug, Default, Getters, PartialEq)]
+pub struct DurationStats {
+    /// The durations, sorted.
+    durations: Vec<f64>,
+    /// The sum of the durations.
+    #[getset(get = "pub")]
+    total: f64,
+}
+
+impl DurationStats {
+    /// Computes the statistics of a set of durations.
+    #[must_use]
+    pub fn new(mut durations: Vec<f64>) -> Self {
+        durations.sort_by(f64::total_cmp);
+        Self {
+            total: durations.iter().sum(),
+            durations,
+        }
+    }
+
+    /// Returns the number of durations.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.durations.len()
+    }
+
+    /// Returns the mean duration, or `None` if there are none.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean(&self) -> Option<f64> {
+        (!self.durations.is_empty()).then(|| self.total / self.durations.len() as f64)
+    }
+
+    /// Returns the median duration, or `None` if there are none.
+    #[must_use]
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+
+    /// Returns the sample standard deviation of the durations, or `None` if there are fewer
+    /// than two.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        (self.durations.len() > 1).then(|| {
+            let squares = self
+                .durations
+                .iter()
+                .map(|duration| (duration - mean).powi(2))
+                .sum::<f64>();
+            (squares / (self.durations.len() - 1) as f64).sqrt()
+        })
+    }
+
+    /// Returns the shortest duration, or `None` if there are none.
+    #[must_use]
+    pub fn min(&self) -> Option<f64> {
+        self.durations.first().copied()
+    }
+
+    /// Returns the longest duration, or `None` if there are none.
+    #[must_use]
+    pub fn max(&self) -> Option<f64> {
+        self.durations.last().copied()
+    }
+
+    /// Returns the `p`th percentile of the durations, interpolating linearly between the two
+    /// nearest ranks, or `None` if there are none.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The percentile, from 0 to 100. Values outside that range are clamped to it.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        let last = self.durations.len().checked_sub(1)?;
+        let rank = p.clamp(0.0, 100.0) / 100.0 * last as f64;
+        let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+        let (low, high) = (self.durations[below], self.durations[above]);
+
+        Some((high - low).mul_add(rank.fract(), low))
+    }
+}
+
+/// Statistics of the intervals or points with one label.
+#[derive(Clone, Debug, Getters, PartialEq)]
+pub struct LabelStats {
+    #[getset(get = "pub")]
+    label: String,
+    /// The durations of the intervals with the label. Points last 0 seconds.
+    #[getset(get = "pub")]
+    durations: DurationStats,
+}
+
+/// Statistics of one tier, or of the tiers with the same name in a set of `TextGrid`s.
+#[derive(Clone, Debug, Getters, PartialEq)]
+pub struct TierStats {
+    #[getset(get = "pub")]
+    name: String,
+    /// Whether the tier is an interval tier rather than a point tier.
+    #[getset(get = "pub")]
+    is_interval: bool,
+    /// The durations of every interval. Points last 0 seconds.
+    #[getset(get = "pub")]
+    durations: DurationStats,
+    /// The statistics of each label, sorted by label.
+    #[getset(get = "pub")]
+    labels: Vec<LabelStats>,
+    /// The total duration of intervals not labelled as silence. 0 for point tiers.
+    #[getset(get = "pub")]
+    speech_time: f64,
+    /// The total duration of intervals labelled as silence. 0 for point tiers.
+    #[getset(get = "pub")]
+    silence_time: f64,
+}
+
+impl TierStats {
+    /// Returns the share of the labelled time that is speech, or `None` if there is none.
+    #[must_use]
+    pub fn speech_ratio(&self) -> Option<f64> {
+        let labelled = self.speech_time + self.silence_time;
+        (labelled > 0.0).then(|| self.speech_time / labelled)
+    }
+}
+
+/// Speaking rates, computed from a tier of units such as syllables or words.
+#[derive(Clone, Debug, Getters, PartialEq)]
+pub struct Rates {
+    /// The tier the units were counted on.
+    #[getset(get = "pub")]
+    unit_tier: String,
+    /// The number of units, not counting intervals or points labelled as silence.
+    #[getset(get = "pub")]
+    units: usize,
+    /// The total duration of the `TextGrid`s.
+    #[getset(get = "pub")]
+    total_time: f64,
+    /// The time spent speaking, excluding silences on the pause tier.
+    #[getset(get = "pub")]
+    speech_time: f64,
+}
+
+impl Rates {
+    /// Returns the units per second over the total time, pauses included, or `None` if there
+    /// is no time.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn speech_rate(&self) -> Option<f64> {
+        (self.total_time > 0.0).then(|| self.units as f64 / self.total_time)
+    }
+
+    /// Returns the units per second of speaking time, pauses excluded, or `None` if there is
+    /// no speaking time.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn articulation_rate(&self) -> Option<f64> {
+        (self.speech_time > 0.0).then(|| self.units as f64 / self.speech_time)
+    }
+}
+
+/// Options for `Stats::new`.
+#[derive(Clone, Debug, Getters, Setters)]
+pub struct StatsOptions {
+    /// The labels that mark silence, compared after trimming whitespace. Defaults to the
+    /// empty label only.
+    #[getset(get = "pub", set = "pub")]
+    silence_labels: Vec<String>,
+    /// The tier of units to compute speaking rates from, if any.
+    #[getset(get = "pub", set = "pub")]
+    unit_tier: Option<String>,
+    /// The interval tier whose silences are excluded from the speaking time. Defaults to the
+    /// unit tier, which then must be an interval tier.
+    #[getset(get = "pub", set = "pub")]
+    pause_tier: Option<String>,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self {
+            silence_labels: vec![String::new()],
+            unit_tier: None,
+            pause_tier: None,
+        }
+    }
+}
+
+impl StatsOptions {
+    fn is_silence(&self, label: &str) -> bool {
+        self.silence_labels
+            .iter()
+            .any(|silence| silence.trim() == label.trim())
+    }
+}
+
+/// Descriptive statistics of a `TextGrid`, or of a set of `TextGrid`s pooled by tier name.
+#[derive(Clone, Debug, Getters, PartialEq)]
+pub struct Stats {
+    /// The number of `TextGrid`s pooled.
+    #[getset(get = "pub")]
+    textgrids: usize,
+    /// The total duration of the `TextGrid`s.
+    #[getset(get = "pub")]
+    total_time: f64,
+    /// The statistics of each tier, in the order the tier names first appear.
+    #[getset(get = "pub")]
+    tiers: Vec<TierStats>,
+    /// The speaking rates, if `StatsOptions` named a unit tier.
+    #[getset(get = "pub")]
+    rates: Option<Rates>,
+}
+
+/// The durations and silence totals of a tier, gathered across `TextGrid`s.
+struct TierTotals {
+    name: String,
+    is_interval: bool,
+    durations: Vec<f64>,
+    labels: BTreeMap<String, Vec<f64>>,
+    speech_time: f64,
+    silence_time: f64,
+}
+
+impl Stats {
+    /// Computes the statistics of a set of `TextGrid`s, pooling the tiers with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `textgrids` - The `TextGrid`s to describe.
+    /// * `options` - The silence labels, and the tiers to compute speaking rates from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if tiers with the same name are of different kinds, or if the unit or
+    /// pause tier is missing from a `TextGrid` or the pause tier is not an interval tier.
+    pub fn new(textgrids: &[TextGrid], options: &StatsOptions) -> Result<Self> {
+        let mut totals = Vec::<TierTotals>::new();
+
+        for tier in textgrids.iter().flat_map(TextGrid::tiers) {
+            let position = totals
+                .iter()
+                .position(|other| other.name == tier.name())
+                .unwrap_or_else(|| {
+                    totals.push(TierTotals {
+                        name: tier.name().to_string(),
+                        is_interval: tier.is_interval(),
+                        durations: Vec::new(),
+                        labels: BTreeMap::new(),
+                        speech_time: 0.0,
+                        silence_time: 0.0,
+                    });
+                    totals.len() - 1
+                });
+            let entry = &mut totals[position];
+            if entry.is_interval != tier.is_interval() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Tier `{}` is an IntervalTier in one TextGrid and a PointTier in another",
+                        entry.name
+                    ),
+                ));
+            }
+
+            for annotation in tier.annotations() {
+                let duration = annotation.xmax() - annotation.xmin();
+                entry.durations.push(duration);
+                entry
+                    .labels
+                    .entry(annotation.text().to_string())
+                    .or_default()
+                    .push(duration);
+                if tier.is_interval() {
+                    if options.is_silence(annotation.text()) {
+                        entry.silence_time += duration;
+                    } else {
+                        entry.speech_time += duration;
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            textgrids: textgrids.len(),
+            total_time: textgrids
+                .iter()
+                .map(|textgrid| textgrid.xmax() - textgrid.xmin())
+                .sum(),
+            tiers: totals
+                .into_iter()
+                .map(|totals| TierStats {
+                    name: totals.name,
+                    is_interval: totals.is_interval,
+                    durations: DurationStats::new(totals.durations),
+                    labels: totals
+                        .labels
+                        .into_iter()
+                        .map(|(label, durations)| LabelStats {
+                            label,
+                            durations: DurationStats::new(durations),
+                        })
+                        .collect(),
+                    speech_time: totals.speech_time,
+                    silence_time: totals.silence_time,
+                })
+                .collect(),
+            rates: options
+                .unit_tier
+                .as_ref()
+                .map(|unit_tier| compute_rates(textgrids, unit_tier, options))
+                .transpose()?,
+        })
+    }
+
+    /// Formats the statistics as CSV, with a row for each tier followed by a row for each of
+    /// its labels. Tier rows also give the speech and silence time, and the unit tier's row
+    /// the speaking rates. Empty fields are not applicable.
+    ///
+    /// # Returns
+    ///
+    /// A vector of strings containing the header and one CSV row per tier or label.
+    #[must_use]
+    pub fn to_csv(&self) -> Vec<String> {
+        let mut rows = vec![format!(
+            "level,tier,label,count,total,mean,median,sd,min,{},max,speech,silence,speech_ratio,speech_rate,articulation_rate",
+            CSV_PERCENTILES.map(|p| format!("p{p}")).join(",")
+        )];
+
+        let number = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+        let durations = |stats: &DurationStats| {
+            let mut fields = vec![
+                stats.count().to_string(),
+                stats.total().to_string(),
+                number(stats.mean()),
+                number(stats.median()),
+                number(stats.std_dev()),
+                number(stats.min()),
+            ];
+            fields.extend(CSV_PERCENTILES.map(|p| number(stats.percentile(p))));
+            fields.push(number(stats.max()));
+            fields.join(",")
+        };
+
+        for tier in &self.tiers {
+            let rates = self
+                .rates
+                .as_ref()
+                .filter(|rates| rates.unit_tier == tier.name);
+            rows.push(format!(
+                "tier,{},,{},{},{},{},{},{}",
+                quote_csv(&tier.name),
+                durations(&tier.durations),
+                number(tier.is_interval.then_some(tier.speech_time)),
+                number(tier.is_interval.then_some(tier.silence_time)),
+                number(tier.speech_ratio()),
+                number(rates.and_then(Rates::speech_rate)),
+                number(rates.and_then(Rates::articulation_rate)),
+            ));
+            for label in &tier.labels {
+                rows.push(format!(
+                    "label,{},{},{},,,,,",
+                    quote_csv(&tier.name),
+                    quote_csv(&label.label),
+                    durations(&label.durations)
+                ));
+            }
+        }
+
+        rows
+    }
+}
+
+/// Counts the units on the unit tier and the speaking time on the pause tier of every
+/// `TextGrid`.
+fn compute_rates(textgrids: &[TextGrid], unit_tier: &str, options: &StatsOptions) -> Result<Rates> {
+    let pause_tier = options.pause_tier.as_deref().unwrap_or(unit_tier);
+    let mut rates = Rates {
+        unit_tier: unit_tier.to_string(),
+        units: 0,
+        total_time: 0.0,
+        speech_time: 0.0,
+    };
+
+    for textgrid in textgrids {
+        let missing = |name: &str| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "Tier `{name}` does not exist in TextGrid `{}`",
+                    textgrid.name()
+                ),
+            )
+        };
+        let units = textgrid
+            .get_tier(unit_tier)
+            .ok_or_else(|| missing(unit_tier))?;
+        let pauses = textgrid
+            .get_tier(pause_tier)
+            .ok_or_else(|| missing(pause_tier))?;
+        let Tier::IntervalTier(pauses) = pauses else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Pause tier `{pause_tier}` must be an IntervalTier"),
+            ));
+        };
+
+        rates.units += units
+            .annotations()
+            .filter(|annotation| !options.is_silence(annotation.text()))
+            .count();
+        rates.total_time += textgrid.xmax() - textgrid.xmin();
+        rates.speech_time += pauses
+            .intervals()
+            .iter()
+            .filter(|interval| !options.is_silence(interval.text()))
+            .map(crate::interval::Interval::get_duration)
+            .sum::<f64>();
+    }
+
+    Ok(rates)
+}
+
+impl TextGrid {
+    /// Computes the statistics of the `TextGrid`, as in `Stats::new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the unit or pause tier is missing, or the pause tier is not an
+    /// interval tier.
+    pub fn stats(&self, options: &StatsOptions) -> Result<Stats> {
+        Stats::new(std::slice::from_ref(self), options)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_stats {
+    use super::{DurationStats, Stats, StatsOptions};
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+    };
+
+    fn interval_tier(name: &str, intervals: &[(f64, f64, &str)]) -> IntervalTier {
+        IntervalTier::new(
+            name.to_string(),
+            0.0,
+            4.0,
+            intervals
+                .iter()
+                .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                .collect(),
+        )
+    }
+
+    fn textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 4.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            interval_tier(
+                "syllables",
+                &[
+                    (0.0, 0.5, ""),
+                    (0.5, 1.0, "dai"),
+                    (1.0, 2.0, "sy"),
+                    (2.0, 3.0, "sil"),
+                    (3.0, 4.0, "bell"),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                4.0,
+                vec![
+                    Point::new(0.7, "H*".to_string()),
+                    Point::new(3.5, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn duration_stats() {
+        let stats = DurationStats::new(vec![4.0, 1.0, 3.0, 2.0]);
+
+        assert_eq!(stats.count(), 4);
+        assert_eq!(*stats.total(), 10.0);
+        assert_eq!(stats.mean(), Some(2.5));
+        assert_eq!(stats.median(), Some(2.5));
+        assert_eq!(stats.min(), Some(1.0));
+        assert_eq!(stats.max(), Some(4.0));
+        assert_eq!(stats.percentile(0.0), Some(1.0));
+        assert_eq!(stats.percentile(25.0), Some(1.75));
+        assert_eq!(stats.percentile(150.0), Some(4.0));
+        assert!((stats.std_dev().unwrap() - 1.290_994_448_735_805_6).abs() < 1e-12);
+
+        let empty = DurationStats::new(Vec::new());
+        assert_eq!(empty.mean(), None);
+        assert_eq!(empty.percentile(50.0), None);
+        assert_eq!(DurationStats::new(vec![1.0]).std_dev(), None);
+    }
+
+    #[test]
+    fn stats() {
+        let mut options = StatsOptions::default();
+        options
+            .set_silence_labels(vec![String::new(), "sil".to_string()])
+            .set_unit_tier(Some("syllables".to_string()));
+        let stats = textgrid().stats(&options).unwrap();
+
+        let syllables = &stats.tiers()[0];
+        assert!(*syllables.is_interval());
+        assert_eq!(syllables.durations().count(), 5);
+        assert_eq!(*syllables.speech_time(), 2.5);
+        assert_eq!(*syllables.silence_time(), 1.5);
+        assert_eq!(syllables.speech_ratio(), Some(0.625));
+        assert_eq!(syllables.labels()[0].label(), "");
+        assert_eq!(syllables.labels()[1].label(), "bell");
+
+        let tones = &stats.tiers()[1];
+        assert_eq!(tones.durations().count(), 2);
+        assert_eq!(tones.speech_ratio(), None);
+
+        let rates = stats.rates().as_ref().unwrap();
+        assert_eq!(*rates.units(), 3);
+        assert_eq!(rates.speech_rate(), Some(0.75));
+        assert_eq!(rates.articulation_rate(), Some(1.2));
+    }
+
+    #[test]
+    fn pooled() {
+        let textgrids = [textgrid(), textgrid()];
+        let stats = Stats::new(&textgrids, &StatsOptions::default()).unwrap();
+
+        assert_eq!(*stats.textgrids(), 2);
+        assert_eq!(*stats.total_time(), 8.0);
+        assert_eq!(stats.tiers().len(), 2);
+        assert_eq!(stats.tiers()[0].durations().count(), 10);
+        assert!(stats.rates().is_none());
+    }
+
+    #[test]
+    fn errors() {
+        let mut options = StatsOptions::default();
+        options.set_unit_tier(Some("missing".to_string()));
+        assert!(textgrid().stats(&options).is_err());
+
+        options
+            .set_unit_tier(Some("syllables".to_string()))
+            .set_pause_tier(Some("tones".to_string()));
+        assert!(textgrid().stats(&options).is_err());
+
+        let mut other = textgrid();
+        other.delete_tier("tones", false);
+        other.push_tier(interval_tier("tones", &[(0.0, 4.0, "")]).into(), false);
+        assert!(Stats::new(&[textgrid(), other], &StatsOptions::default()).is_err());
+    }
+
+    #[test]
+    fn to_csv() {
+        let mut options = StatsOptions::default();
+        options.set_unit_tier(Some("syllables".to_string()));
+        let rows = textgrid().stats(&options).unwrap().to_csv();
+
+        let columns = rows[0].split(',').count();
+        assert!(rows.iter().all(|row| row.split(',').count() == columns));
+        assert_eq!(rows.len(), 1 + 1 + 5 + 1 + 2);
+        assert!(rows[1].starts_with("tier,syllables,,5,4,0.8,1,"));
+        assert!(rows[1].ends_with(",3.5,0.5,0.875,1,1.1428571428571428"));
+        assert!(rows[2].starts_with("label,syllables,,1,0.5,"));
+        assert!(rows[7].ends_with(",,,,,"));
+    }
+}
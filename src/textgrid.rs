@@ -1,14 +1,20 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
     fs::{self, File},
-    io::{Read, Result, Write},
+    io::{Error, ErrorKind, Read, Result, Write},
     path::PathBuf,
 };
 
 use derive_more::Constructor;
 use getset::{Getters, Setters};
 
-use crate::{interval::Tier as IntervalTier, parse_textgrid, point::Tier as PointTier};
+use crate::{
+    interval::Tier as IntervalTier,
+    parse_textgrid,
+    point::Tier as PointTier,
+    tier::{Annotation, AnnotationTier, TierIssue},
+    utilities::check_scale_factor,
+};
 
 /// Represents a tier in a `TextGrid`.
 #[derive(Clone, Debug)]
@@ -17,14 +23,66 @@ pub enum Tier {
     PointTier(PointTier),
 }
 
-#[allow(dead_code)]
 impl Tier {
+    /// Borrows a `Tier` as an `IntervalTier`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `IntervalTier` if the `Tier` is an `IntervalTier`, otherwise None.
+    #[must_use]
+    pub const fn as_interval(&self) -> Option<&IntervalTier> {
+        match self {
+            Self::IntervalTier(interval_tier) => Some(interval_tier),
+            Self::PointTier(_) => None,
+        }
+    }
+
+    /// Mutably borrows a `Tier` as an `IntervalTier`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `IntervalTier` if the `Tier` is an `IntervalTier`, otherwise None.
+    #[must_use]
+    pub const fn as_interval_mut(&mut self) -> Option<&mut IntervalTier> {
+        match self {
+            Self::IntervalTier(interval_tier) => Some(interval_tier),
+            Self::PointTier(_) => None,
+        }
+    }
+
+    /// Borrows a `Tier` as a `PointTier`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `PointTier` if the `Tier` is a `PointTier`, otherwise None.
+    #[must_use]
+    pub const fn as_point(&self) -> Option<&PointTier> {
+        match self {
+            Self::PointTier(point_tier) => Some(point_tier),
+            Self::IntervalTier(_) => None,
+        }
+    }
+
+    /// Mutably borrows a `Tier` as a `PointTier`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `PointTier` if the `Tier` is a `PointTier`, otherwise None.
+    #[must_use]
+    pub const fn as_point_mut(&mut self) -> Option<&mut PointTier> {
+        match self {
+            Self::PointTier(point_tier) => Some(point_tier),
+            Self::IntervalTier(_) => None,
+        }
+    }
+
     /// Unwraps a `Tier` into an `IntervalTier`.
     ///
     /// # Returns
     ///
     /// Returns the `IntervalTier` if the `Tier` is an `IntervalTier`, otherwise None.
-    const fn get_interval_tier(&self) -> Option<&IntervalTier> {
+    #[must_use]
+    pub fn into_interval(self) -> Option<IntervalTier> {
         match self {
             Self::IntervalTier(interval_tier) => Some(interval_tier),
             Self::PointTier(_) => None,
@@ -36,12 +94,203 @@ impl Tier {
     /// # Returns
     ///
     /// Returns the `PointTier` if the `Tier` is a `PointTier`, otherwise None.
-    const fn get_point_tier(&self) -> Option<&PointTier> {
+    #[must_use]
+    pub fn into_point(self) -> Option<PointTier> {
         match self {
             Self::PointTier(point_tier) => Some(point_tier),
             Self::IntervalTier(_) => None,
         }
     }
+
+    /// Returns `true` if the `Tier` is an `IntervalTier`.
+    #[must_use]
+    pub const fn is_interval(&self) -> bool {
+        matches!(self, Self::IntervalTier(_))
+    }
+
+    /// Returns `true` if the `Tier` is a `PointTier`.
+    #[must_use]
+    pub const fn is_point(&self) -> bool {
+        matches!(self, Self::PointTier(_))
+    }
+
+    /// Returns the name of the tier.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        AnnotationTier::name(self)
+    }
+
+    /// Sets the name of the tier.
+    pub fn set_name(&mut self, name: String) {
+        AnnotationTier::set_name(self, name);
+    }
+
+    /// Returns the start time of the tier.
+    #[must_use]
+    pub fn xmin(&self) -> f64 {
+        AnnotationTier::xmin(self)
+    }
+
+    /// Returns the end time of the tier.
+    #[must_use]
+    pub fn xmax(&self) -> f64 {
+        AnnotationTier::xmax(self)
+    }
+
+    /// Calls `shift` on the underlying tier.
+    pub fn shift(&mut self, dt: f64) {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.shift(dt),
+            Self::PointTier(point_tier) => point_tier.shift(dt),
+        }
+    }
+
+    /// Calls `scale` on the underlying tier.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `factor` is not positive and finite.
+    pub fn scale(&mut self, factor: f64, anchor: f64) -> Result<()> {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.scale(factor, anchor),
+            Self::PointTier(point_tier) => point_tier.scale(factor, anchor),
+        }
+    }
+
+    /// Calls `extract_part` on the underlying tier.
+    #[must_use]
+    pub fn extract_part(&self, t0: f64, t1: f64, preserve_times: bool) -> Self {
+        match self {
+            Self::IntervalTier(interval_tier) => {
+                Self::IntervalTier(interval_tier.extract_part(t0, t1, preserve_times))
+            }
+            Self::PointTier(point_tier) => {
+                Self::PointTier(point_tier.extract_part(t0, t1, preserve_times))
+            }
+        }
+    }
+}
+
+impl From<IntervalTier> for Tier {
+    fn from(interval_tier: IntervalTier) -> Self {
+        Self::IntervalTier(interval_tier)
+    }
+}
+
+impl From<PointTier> for Tier {
+    fn from(point_tier: PointTier) -> Self {
+        Self::PointTier(point_tier)
+    }
+}
+
+impl TryFrom<Tier> for IntervalTier {
+    type Error = Error;
+
+    fn try_from(tier: Tier) -> Result<Self> {
+        match tier {
+            Tier::IntervalTier(interval_tier) => Ok(interval_tier),
+            Tier::PointTier(point_tier) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Tier `{}` is a PointTier, not an IntervalTier",
+                    point_tier.name()
+                ),
+            )),
+        }
+    }
+}
+
+impl TryFrom<Tier> for PointTier {
+    type Error = Error;
+
+    fn try_from(tier: Tier) -> Result<Self> {
+        match tier {
+            Tier::PointTier(point_tier) => Ok(point_tier),
+            Tier::IntervalTier(interval_tier) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Tier `{}` is an IntervalTier, not a PointTier",
+                    interval_tier.name()
+                ),
+            )),
+        }
+    }
+}
+
+impl AnnotationTier for Tier {
+    fn name(&self) -> &str {
+        match self {
+            Self::IntervalTier(interval_tier) => AnnotationTier::name(interval_tier),
+            Self::PointTier(point_tier) => AnnotationTier::name(point_tier),
+        }
+    }
+
+    fn set_name(&mut self, name: String) {
+        match self {
+            Self::IntervalTier(interval_tier) => {
+                interval_tier.set_name(name);
+            }
+            Self::PointTier(point_tier) => {
+                point_tier.set_name(name);
+            }
+        }
+    }
+
+    fn xmin(&self) -> f64 {
+        match self {
+            Self::IntervalTier(interval_tier) => *interval_tier.xmin(),
+            Self::PointTier(point_tier) => *point_tier.xmin(),
+        }
+    }
+
+    fn xmax(&self) -> f64 {
+        match self {
+            Self::IntervalTier(interval_tier) => *interval_tier.xmax(),
+            Self::PointTier(point_tier) => *point_tier.xmax(),
+        }
+    }
+
+    fn set_xmin(&mut self, xmin: f64, warn: Option<bool>) {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.set_xmin(xmin, warn),
+            Self::PointTier(point_tier) => point_tier.set_xmin(xmin, warn),
+        }
+    }
+
+    fn set_xmax(&mut self, xmax: f64, warn: Option<bool>) {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.set_xmax(xmax, warn),
+            Self::PointTier(point_tier) => point_tier.set_xmax(xmax, warn),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.get_size(),
+            Self::PointTier(point_tier) => point_tier.get_size(),
+        }
+    }
+
+    fn annotations(&self) -> Box<dyn Iterator<Item = Annotation<'_>> + '_> {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.annotations(),
+            Self::PointTier(point_tier) => point_tier.annotations(),
+        }
+    }
+
+    fn annotations_in(&self, t0: f64, t1: f64) -> Vec<Annotation<'_>> {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.annotations_in(t0, t1),
+            Self::PointTier(point_tier) => point_tier.annotations_in(t0, t1),
+        }
+    }
+
+    fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue> {
+        match self {
+            Self::IntervalTier(interval_tier) => interval_tier.check_overlaps(epsilon),
+            Self::PointTier(point_tier) => point_tier.check_overlaps(epsilon),
+        }
+    }
 }
 
 impl Default for Tier {
@@ -110,23 +359,11 @@ impl TextGrid {
 
         if warn.into().unwrap_or_default() {
             for tier in &self.tiers {
-                match tier {
-                    Tier::IntervalTier(interval_tier) => {
-                        if *interval_tier.xmin() < xmin {
-                            eprintln!(
-                                "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
-                                interval_tier.name(), interval_tier.xmin(), xmin
-                            );
-                        }
-                    }
-                    Tier::PointTier(point_tier) => {
-                        if *point_tier.xmin() < xmin {
-                            eprintln!(
-                                "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
-                                point_tier.name(), point_tier.xmin(), xmin
-                            );
-                        }
-                    }
+                if tier.xmin() < xmin {
+                    eprintln!(
+                        "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
+                        tier.name(), tier.xmin(), xmin
+                    );
                 }
             }
         }
@@ -157,23 +394,11 @@ impl TextGrid {
 
         if warn.into().unwrap_or_default() {
             for tier in &self.tiers {
-                match tier {
-                    Tier::IntervalTier(interval_tier) => {
-                        if *interval_tier.xmax() > xmax {
-                            eprintln!(
-                                "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
-                                interval_tier.name(), interval_tier.xmax(), xmax
-                            );
-                        }
-                    }
-                    Tier::PointTier(point_tier) => {
-                        if *point_tier.xmax() > xmax {
-                            eprintln!(
-                                "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
-                                point_tier.name(), point_tier.xmax(), xmax
-                            );
-                        }
-                    }
+                if tier.xmax() > xmax {
+                    eprintln!(
+                        "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
+                        tier.name(), tier.xmax(), xmax
+                    );
                 }
             }
         }
@@ -183,61 +408,38 @@ impl TextGrid {
 
     /// Pushes a new, user-made tier to the `TextGrid`.
     ///
+    /// If a tier with the same name already exists, the new tier is always renamed by appending
+    /// the lowest number that makes its name unique, e.g. `words` becomes `words1`, whether or
+    /// not `warn` is set.
+    ///
     /// # Arguments
     ///
     /// * `tier` - The tier to be added.
-    /// * `warn` - If Some(true), displays a warning if the tier has a minimum or maximum point
-    ///            that is outside the range of the `TextGrid`.
+    /// * `warn` - If Some(true), displays a warning if the tier is renamed, or has a minimum or
+    ///   maximum point that is outside the range of the `TextGrid`.
     pub fn push_tier<W: Into<Option<bool>> + Copy>(&mut self, mut tier: Tier, warn: W) {
-        let name = match &tier {
-            Tier::IntervalTier(interval_tier) => interval_tier.name(),
-            Tier::PointTier(point_tier) => point_tier.name(),
-        };
+        let name = tier.name().to_string();
 
-        let mut increment = 0;
-        let mut new_name = name.to_string();
-        while self.get_tier(&new_name).is_some() {
-            increment += 1;
-            new_name = format!("{name}{increment}");
-        }
-        if increment > 0 && warn.into().unwrap_or_default() {
-            eprintln!("Warning: Tier name `{name}` already exists. Renaming to `{new_name}`");
+        let new_name = self.unique_tier_name(&name);
+        if new_name != name {
+            if warn.into().unwrap_or_default() {
+                eprintln!("Warning: Tier name `{name}` already exists. Renaming to `{new_name}`");
+            }
+            tier.set_name(new_name);
         }
 
         if warn.into().unwrap_or_default() {
-            match tier {
-                Tier::IntervalTier(ref mut interval_tier) => {
-                    interval_tier.set_name(new_name);
-
-                    if *interval_tier.xmin() < self.xmin {
-                        eprintln!(
-                            "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
-                            interval_tier.name(), interval_tier.xmin(), self.xmin
-                        );
-                    }
-                    if *interval_tier.xmax() > self.xmax {
-                        eprintln!(
-                            "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
-                            interval_tier.name(), interval_tier.xmax(), self.xmax
-                        );
-                    }
-                }
-                Tier::PointTier(ref mut point_tier) => {
-                    point_tier.set_name(new_name);
-
-                    if *point_tier.xmin() < self.xmin {
-                        eprintln!(
-                            "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
-                            point_tier.name(), point_tier.xmin(), self.xmin
-                        );
-                    }
-                    if *point_tier.xmax() > self.xmax {
-                        eprintln!(
-                            "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
-                            point_tier.name(), point_tier.xmax(), self.xmax
-                        );
-                    }
-                }
+            if tier.xmin() < self.xmin {
+                eprintln!(
+                    "Warning: Tier `{}` has a minimum point of {} but the TextGrid has an xmin of {}",
+                    tier.name(), tier.xmin(), self.xmin
+                );
+            }
+            if tier.xmax() > self.xmax {
+                eprintln!(
+                    "Warning: Tier `{}` has a maximum point of {} but the TextGrid has an xmax of {}",
+                    tier.name(), tier.xmax(), self.xmax
+                );
             }
         }
 
@@ -255,10 +457,121 @@ impl TextGrid {
     /// Returns the tier if it exists, otherwise None.
     #[must_use]
     pub fn get_tier(&self, name: &str) -> Option<&Tier> {
-        self.tiers.iter().find(|tier| match tier {
-            Tier::IntervalTier(interval_tier) => interval_tier.name() == name,
-            Tier::PointTier(point_tier) => point_tier.name() == name,
-        })
+        self.tiers.iter().find(|tier| tier.name() == name)
+    }
+
+    /// Gets a mutable reference to a tier using it's name.
+    ///
+    /// Renaming the tier or moving it outside the `TextGrid`'s range through this reference is not
+    /// checked; use `edit_tier` to have the `TextGrid`'s invariants restored afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to get.
+    ///
+    /// # Returns
+    ///
+    /// Returns the tier if it exists, otherwise None.
+    #[must_use]
+    pub fn tier_mut(&mut self, name: &str) -> Option<&mut Tier> {
+        self.tiers.iter_mut().find(|tier| tier.name() == name)
+    }
+
+    /// Edits a tier in place using it's name.
+    ///
+    /// Afterwards, the tier is renamed as in `push_tier` if its new name clashes with another
+    /// tier's, and the `TextGrid`'s bounds are widened to cover the tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to edit.
+    /// * `edit` - A closure given mutable access to the tier.
+    ///
+    /// # Returns
+    ///
+    /// The value returned by `edit` if the tier exists, otherwise None.
+    pub fn edit_tier<R, F: FnOnce(&mut Tier) -> R>(&mut self, name: &str, edit: F) -> Option<R> {
+        let index = self.tiers.iter().position(|tier| tier.name() == name)?;
+        let mut tier = self.tiers.remove(index);
+
+        let result = edit(&mut tier);
+
+        let unique_name = self.unique_tier_name(tier.name());
+        tier.set_name(unique_name);
+
+        self.xmin = self.xmin.min(tier.xmin());
+        self.xmax = self.xmax.max(tier.xmax());
+        self.tiers.insert(index, tier);
+
+        Some(result)
+    }
+
+    /// Returns `name`, or `name` followed by the lowest number that no tier is named yet.
+    fn unique_tier_name(&self, name: &str) -> String {
+        let mut increment = 0;
+        let mut unique_name = name.to_string();
+        while self.get_tier(&unique_name).is_some() {
+            increment += 1;
+            unique_name = format!("{name}{increment}");
+        }
+
+        unique_name
+    }
+
+    /// Gets an interval tier using it's name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to get.
+    ///
+    /// # Returns
+    ///
+    /// Returns the tier if it exists and is an `IntervalTier`, otherwise None.
+    #[must_use]
+    pub fn interval_tier(&self, name: &str) -> Option<&IntervalTier> {
+        self.get_tier(name).and_then(Tier::as_interval)
+    }
+
+    /// Gets a point tier using it's name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to get.
+    ///
+    /// # Returns
+    ///
+    /// Returns the tier if it exists and is a `PointTier`, otherwise None.
+    #[must_use]
+    pub fn point_tier(&self, name: &str) -> Option<&PointTier> {
+        self.get_tier(name).and_then(Tier::as_point)
+    }
+
+    /// Gets a mutable reference to an interval tier using it's name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to get.
+    ///
+    /// # Returns
+    ///
+    /// Returns the tier if it exists and is an `IntervalTier`, otherwise None.
+    #[must_use]
+    pub fn interval_tier_mut(&mut self, name: &str) -> Option<&mut IntervalTier> {
+        self.tier_mut(name).and_then(Tier::as_interval_mut)
+    }
+
+    /// Gets a mutable reference to a point tier using it's name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tier to get.
+    ///
+    /// # Returns
+    ///
+    /// Returns the tier if it exists and is a `PointTier`, otherwise None.
+    #[must_use]
+    pub fn point_tier_mut(&mut self, name: &str) -> Option<&mut PointTier> {
+        self.tier_mut(name).and_then(Tier::as_point_mut)
     }
 
     /// Deletes a tier using it's name.
@@ -268,10 +581,7 @@ impl TextGrid {
     /// * `name` - The name of the tier to delete.
     /// * `warn` - If true, displays a warning if the tier does not exist.
     pub fn delete_tier<W: Into<Option<bool>> + Copy>(&mut self, name: &str, warn: W) {
-        let index = self.tiers.iter().position(|tier| match tier {
-            Tier::IntervalTier(interval_tier) => interval_tier.name() == name,
-            Tier::PointTier(point_tier) => point_tier.name() == name,
-        });
+        let index = self.tiers.iter().position(|tier| tier.name() == name);
 
         if let Some(index) = index {
             self.tiers.remove(index);
@@ -309,19 +619,32 @@ impl TextGrid {
 
             if parent_path.pop() {
                 fs::create_dir_all(parent_path)?;
-            };
+            }
 
             File::create(path)?
         };
 
+        self.write_to(&mut file, format)
+    }
+
+    /// Writes the `TextGrid` in the specified format to any writer, such as an open file or
+    /// standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to write to.
+    /// * `format` - The output format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was a problem writing.
+    pub fn write_to<W: Write>(&self, writer: &mut W, format: OutputFormat) -> Result<()> {
         let textgrid_data = match format {
             OutputFormat::Long => self.format_as_long(),
             OutputFormat::Short => self.format_as_short(),
         };
 
-        file.write_all(textgrid_data.join("\n").as_bytes())?;
-
-        Ok(())
+        writer.write_all(textgrid_data.join("\n").as_bytes())
     }
 
     /// Outputs a String vector containing the `TextGrid` to a file in the long format.
@@ -430,45 +753,26 @@ impl TextGrid {
         out_strings
     }
 
-    /// Checks the `TextGrid` for overlapping intervals or duplicate points.
+    /// Checks every tier of the `TextGrid` for gaps, overlaps, duplicate points and other
+    /// timing issues, as in `AnnotationTier::check_overlaps`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - Differences in time of at most `epsilon` are ignored.
     ///
     /// # Returns
     ///
-    /// Returns Some([`tier_name`, (`index1`, `index2`)]) if an overlapping interval or point is found, otherwise None.
+    /// The issues found, each with the name of its tier, in tier order.
     #[must_use]
-    pub fn check_overlaps(&self) -> Option<Vec<(String, (u64, u64))>> {
-        let mut overlaps: Vec<(String, (u64, u64))> = Vec::new();
-
-        for tier in &self.tiers {
-            match tier {
-                Tier::IntervalTier(interval_tier) => {
-                    if let Some(interval_overlaps) = interval_tier.check_overlaps() {
-                        overlaps.append(
-                            &mut interval_overlaps
-                                .into_iter()
-                                .map(|overlap| (interval_tier.name().into(), overlap))
-                                .collect(),
-                        );
-                    }
-                }
-                Tier::PointTier(point_tier) => {
-                    if let Some(point_overlaps) = point_tier.check_overlaps() {
-                        overlaps.append(
-                            &mut point_overlaps
-                                .into_iter()
-                                .map(|overlap| (point_tier.name().into(), overlap))
-                                .collect(),
-                        );
-                    }
-                }
-            }
-        }
-
-        if overlaps.is_empty() {
-            None
-        } else {
-            Some(overlaps)
-        }
+    pub fn check_overlaps(&self, epsilon: f64) -> Vec<(String, TierIssue)> {
+        self.tiers
+            .iter()
+            .flat_map(|tier| {
+                tier.check_overlaps(epsilon)
+                    .into_iter()
+                    .map(|issue| (tier.name().to_string(), issue))
+            })
+            .collect()
     }
 
     /// Calls `fix_overlaps` on all `IntervalTier`s in the `TextGrid`.
@@ -476,8 +780,8 @@ impl TextGrid {
     /// # Arguments
     ///
     /// * `prefer_first` - If true, the first interval's `xmax` will be raised or lowered to the
-    ///                     new interval's `xmin` in the case of a gap or overlap. If false, the
-    ///                     second interval's `xmin` will be raised or lowered to the first interval's.
+    ///   new interval's `xmin` in the case of a gap or overlap. If false, the
+    ///   second interval's `xmin` will be raised or lowered to the first interval's.
     pub fn fix_boundaries<P: Into<Option<bool>> + Copy>(&mut self, prefer_first: P) {
         for tier in &mut self.tiers {
             match tier {
@@ -504,6 +808,91 @@ impl TextGrid {
             }
         }
     }
+
+    /// Shifts every time in the `TextGrid`, including the bounds of the `TextGrid` and its
+    /// tiers, by `dt` seconds, like Praat's "Shift times by".
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The number of seconds to shift by. Negative values shift towards zero.
+    pub fn shift(&mut self, dt: f64) {
+        self.xmin += dt;
+        self.xmax += dt;
+        for tier in &mut self.tiers {
+            tier.shift(dt);
+        }
+    }
+
+    /// Scales every time in the `TextGrid`, including the bounds of the `TextGrid` and its tiers,
+    /// by `factor` around `anchor`. For example, a factor of `2.0` with an anchor of `0.0` doubles
+    /// every time, as after slowing the audio down to half speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The scaling factor.
+    /// * `anchor` - The time that stays in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `factor` is not positive and finite, in which case the `TextGrid` is
+    /// left unchanged.
+    pub fn scale(&mut self, factor: f64, anchor: f64) -> Result<()> {
+        check_scale_factor(factor)?;
+
+        self.xmin = (self.xmin - anchor).mul_add(factor, anchor);
+        self.xmax = (self.xmax - anchor).mul_add(factor, anchor);
+        for tier in &mut self.tiers {
+            tier.scale(factor, anchor)?;
+        }
+
+        Ok(())
+    }
+
+    /// Extracts the part of the `TextGrid` between `t0` and `t1`, like Praat's "Extract part".
+    /// Intervals straddling `t0` or `t1` are cut at them, and intervals and points outside are
+    /// dropped. The range is clipped to the bounds of the `TextGrid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `t0` - The start of the part.
+    /// * `t1` - The end of the part.
+    /// * `preserve_times` - If `true`, keeps the original times. If `false`, shifts the part so
+    ///   that it starts at zero.
+    ///
+    /// # Returns
+    ///
+    /// A new `TextGrid` with the same name and tiers, spanning `t0` to `t1`, or `0.0` to `t1 - t0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clipped range is empty.
+    pub fn extract_part(&self, t0: f64, t1: f64, preserve_times: bool) -> Result<Self> {
+        let t0 = t0.max(self.xmin);
+        let t1 = t1.min(self.xmax);
+        if t0 >= t1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Cannot extract {t0} to {t1} from `{}`, which spans {} to {}",
+                    self.name, self.xmin, self.xmax
+                ),
+            ));
+        }
+
+        let tiers = self
+            .tiers
+            .iter()
+            .map(|tier| tier.extract_part(t0, t1, preserve_times))
+            .collect();
+
+        let (xmin, xmax) = if preserve_times {
+            (t0, t1)
+        } else {
+            (0.0, t1 - t0)
+        };
+
+        Ok(Self::new(xmin, xmax, tiers, self.name.clone()))
+    }
 }
 
 impl Display for TextGrid {
@@ -659,6 +1048,166 @@ mod test_textgrid {
         assert_eq!(textgrid.get_size(), 2);
     }
 
+    #[test]
+    fn push_tier_renames_duplicates() {
+        let mut textgrid = TextGrid::new(0.0, 10.0, Vec::new(), "test".to_string());
+        for warn in [false, false, true] {
+            textgrid.push_tier(
+                IntervalTier::new("words".to_string(), 0.0, 10.0, vec![]).into(),
+                warn,
+            );
+        }
+
+        let names = textgrid
+            .tiers()
+            .iter()
+            .map(|tier| tier.name().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["words", "words1", "words2"]);
+    }
+
+    #[test]
+    fn typed_lookup() {
+        let mut textgrid = TextGrid::new(0.0, 10.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new("words".to_string(), 0.0, 10.0, vec![]).into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new("tones".to_string(), 0.0, 10.0, vec![]).into(),
+            false,
+        );
+
+        assert!(textgrid.interval_tier("words").is_some());
+        assert!(textgrid.interval_tier("tones").is_none());
+        assert!(textgrid.point_tier("tones").is_some());
+        assert!(textgrid.point_tier("missing").is_none());
+    }
+
+    #[test]
+    fn edit_tier() {
+        let mut textgrid = TextGrid::new(0.0, 10.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                10.0,
+                vec![Interval::new(0.0, 10.0, "daisy".to_string())],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new("tones".to_string(), 0.0, 10.0, vec![]).into(),
+            false,
+        );
+
+        textgrid
+            .interval_tier_mut("words")
+            .unwrap()
+            .edit_intervals(|intervals| {
+                intervals[0].set_text("bell".to_string());
+            });
+        assert_eq!(
+            textgrid.interval_tier("words").unwrap().intervals()[0].text(),
+            "bell"
+        );
+
+        let edited = textgrid.edit_tier("tones", |tier| {
+            tier.set_name("words".to_string());
+            tier.as_point_mut().unwrap().edit_points(|points| {
+                points.push(Point::new(12.0, "H*".to_string()));
+            });
+        });
+        assert!(edited.is_some());
+        assert!(textgrid.point_tier("words1").is_some());
+        assert_eq!(*textgrid.xmax(), 12.0);
+
+        assert!(textgrid.edit_tier("missing", |_| ()).is_none());
+        assert!(textgrid.tier_mut("missing").is_none());
+    }
+
+    fn timed_textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Interval::new(0.0, 1.0, "daisy".to_string()),
+                    Interval::new(1.0, 2.0, "daisy".to_string()),
+                    Interval::new(2.0, 3.0, "bell".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Point::new(0.5, "H*".to_string()),
+                    Point::new(2.5, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    #[test]
+    fn shift_and_scale() {
+        let mut textgrid = timed_textgrid();
+
+        textgrid.shift(1.0);
+        assert_eq!(*textgrid.xmax(), 4.0);
+        assert_eq!(
+            *textgrid.interval_tier("words").unwrap().intervals()[0].xmin(),
+            1.0
+        );
+
+        textgrid.scale(2.0, 1.0).unwrap();
+        assert_eq!(*textgrid.xmin(), 1.0);
+        assert_eq!(*textgrid.xmax(), 7.0);
+        assert_eq!(
+            *textgrid.point_tier("tones").unwrap().points()[1].number(),
+            6.0
+        );
+
+        assert!(textgrid.scale(0.0, 0.0).is_err());
+        assert_eq!(*textgrid.xmax(), 7.0);
+    }
+
+    #[test]
+    fn extract_part() {
+        let textgrid = timed_textgrid();
+
+        let part = textgrid.extract_part(0.5, 2.5, false).unwrap();
+        assert_eq!(*part.xmin(), 0.0);
+        assert_eq!(*part.xmax(), 2.0);
+
+        let words = part.interval_tier("words").unwrap();
+        assert_eq!(words.get_size(), 3);
+        assert_eq!(*words.intervals()[0].xmax(), 0.5);
+        assert_eq!(*words.intervals()[2].xmax(), 2.0);
+        assert!(words.check_overlaps(0.0).is_empty());
+
+        let tones = part.point_tier("tones").unwrap();
+        assert_eq!(tones.get_size(), 2);
+        assert_eq!(*tones.points()[1].number(), 2.0);
+
+        let part = textgrid.extract_part(1.0, 10.0, true).unwrap();
+        assert_eq!(*part.xmax(), 3.0);
+        assert_eq!(part.interval_tier("words").unwrap().get_size(), 2);
+        assert_eq!(part.point_tier("tones").unwrap().get_size(), 1);
+
+        assert!(textgrid.extract_part(4.0, 5.0, true).is_err());
+    }
+
     #[test]
     fn from_pathbuf() {
         let cwd = env::current_dir();
@@ -783,25 +1332,53 @@ mod test_textgrid {
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod test_tier {
     use crate::interval::Tier as IntervalTier;
     use crate::point::Tier as PointTier;
     use crate::textgrid::Tier;
 
     #[test]
-    fn get_interval_tier() {
+    fn as_interval() {
         let interval_tier = IntervalTier::new("test".to_string(), 0.0, 10.0, vec![]);
-        let tier = Tier::IntervalTier(interval_tier);
+        let mut tier = Tier::IntervalTier(interval_tier);
+
+        assert!(tier.as_interval().is_some());
+        assert!(tier.as_point().is_none());
+        assert!(tier.is_interval());
 
-        assert!(tier.get_interval_tier().is_some());
+        tier.as_interval_mut()
+            .unwrap()
+            .set_name("words".to_string());
+        assert_eq!(tier.name(), "words");
+        assert_eq!(tier.xmax(), 10.0);
+
+        assert!(tier.clone().into_point().is_none());
+        assert!(tier.into_interval().is_some());
     }
 
     #[test]
-    fn get_point_tier() {
+    fn as_point() {
         let point_tier = PointTier::new("test".to_string(), 0.0, 10.0, vec![]);
-        let tier = Tier::PointTier(point_tier);
+        let mut tier = Tier::PointTier(point_tier);
+
+        assert!(tier.as_point().is_some());
+        assert!(tier.as_interval().is_none());
+        assert!(tier.is_point());
+
+        tier.as_point_mut().unwrap().set_xmin(1.0, None);
+        assert_eq!(tier.xmin(), 1.0);
+
+        assert!(tier.into_point().is_some());
+    }
+
+    #[test]
+    fn try_from() {
+        let interval_tier = IntervalTier::new("test".to_string(), 0.0, 10.0, vec![]);
+        let tier = Tier::from(interval_tier);
 
-        assert!(tier.get_point_tier().is_some());
+        assert!(PointTier::try_from(tier.clone()).is_err());
+        assert_eq!(IntervalTier::try_from(tier).unwrap().name(), "test");
     }
 
     #[test]
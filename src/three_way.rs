@@ -0,0 +1,522 @@
+use std::fmt::{self, Display, Formatter};
+
+use getset::Getters;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    interval::{Interval, Tier as IntervalTier},
+    point::{Point, Tier as PointTier},
+    textgrid::{TextGrid, Tier},
+    tier::AnnotationTier,
+    utilities::{group_intervals, interval_boundaries},
+};
+
+/// A part of a three-way merge that both sides changed in different ways.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "conflict", rename_all = "snake_case")
+)]
+pub enum Conflict {
+    /// Both sides changed the `(xmin, xmax)` of the `TextGrid`. The merge spans both versions.
+    Bounds {
+        base: (f64, f64),
+        ours: (f64, f64),
+        theirs: (f64, f64),
+    },
+    /// Both sides changed a whole tier, e.g. one deleted it while the other edited it, or both
+    /// added a tier with the same name. The merge keeps our version, if any.
+    Tier { tier: String, reason: String },
+    /// Both sides changed the intervals or points in the same stretch of a tier. The merge
+    /// replaces the stretch with a single conflict interval or point.
+    Region {
+        tier: String,
+        xmin: f64,
+        xmax: f64,
+        base: Vec<String>,
+        ours: Vec<String>,
+        theirs: Vec<String>,
+    },
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Bounds { base, ours, theirs } => write!(
+                f,
+                "TextGrid bounds changed from [{}, {}] to [{}, {}] in ours and [{}, {}] in theirs",
+                base.0, base.1, ours.0, ours.1, theirs.0, theirs.1
+            ),
+            Self::Tier { tier, reason } => write!(f, "`{tier}`: {reason}"),
+            Self::Region {
+                tier,
+                xmin,
+                xmax,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "`{tier}`: [{xmin}, {xmax}] changed from \"{}\" to \"{}\" in ours and \"{}\" in theirs",
+                base.join(" | "),
+                ours.join(" | "),
+                theirs.join(" | ")
+            ),
+        }
+    }
+}
+
+/// The result of `TextGrid::merge_three_way`.
+#[derive(Clone, Debug, Getters)]
+pub struct ThreeWayMerge {
+    /// The merged `TextGrid`, with conflicting stretches marked.
+    #[getset(get = "pub")]
+    textgrid: TextGrid,
+    /// The conflicts, ordered by tier and then by time.
+    #[getset(get = "pub")]
+    conflicts: Vec<Conflict>,
+}
+
+impl ThreeWayMerge {
+    /// Returns `true` if the merge has no conflicts.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Consumes the merge, returning the merged `TextGrid` and the conflicts.
+    #[must_use]
+    pub fn into_parts(self) -> (TextGrid, Vec<Conflict>) {
+        (self.textgrid, self.conflicts)
+    }
+}
+
+impl TextGrid {
+    /// Merges two versions of a `TextGrid` edited concurrently from a common base, like
+    /// `git merge` does for text.
+    ///
+    /// Tiers are matched by name. Within a tier, the timeline is cut at the boundaries, or
+    /// point times, that all three versions share, and each stretch between them is taken
+    /// from whichever side changed it. Edits to different tiers, or to stretches of the same
+    /// tier separated by an untouched boundary, therefore merge automatically.
+    ///
+    /// If both sides changed a stretch differently, the stretch is replaced by a single
+    /// interval, or point, labelled `<<<<<<< ours ======= theirs >>>>>>>` with the labels of
+    /// each side, and a `Conflict` is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The common ancestor.
+    /// * `ours` - Our version, whose name the merge takes.
+    /// * `theirs` - Their version.
+    #[must_use]
+    pub fn merge_three_way(base: &Self, ours: &Self, theirs: &Self) -> ThreeWayMerge {
+        let mut conflicts = Vec::new();
+
+        let bounds = |textgrid: &Self| (*textgrid.xmin(), *textgrid.xmax());
+        let (base_bounds, ours_bounds, theirs_bounds) =
+            (bounds(base), bounds(ours), bounds(theirs));
+        if choose(&base_bounds, &ours_bounds, &theirs_bounds, PartialEq::eq).is_none() {
+            conflicts.push(Conflict::Bounds {
+                base: base_bounds,
+                ours: ours_bounds,
+                theirs: theirs_bounds,
+            });
+        }
+        let (xmin, xmax) = merge_bounds(base_bounds, ours_bounds, theirs_bounds);
+
+        let mut names = Vec::<&str>::new();
+        for tier in base
+            .tiers()
+            .iter()
+            .chain(ours.tiers())
+            .chain(theirs.tiers())
+        {
+            if !names.contains(&tier.name()) {
+                names.push(tier.name());
+            }
+        }
+
+        let mut tiers = Vec::new();
+        for name in names {
+            let merged = merge_tier(
+                name,
+                base.get_tier(name),
+                ours.get_tier(name),
+                theirs.get_tier(name),
+                &mut conflicts,
+            );
+            tiers.extend(merged);
+        }
+
+        ThreeWayMerge {
+            textgrid: Self::new(xmin, xmax, tiers, ours.name().clone()),
+            conflicts,
+        }
+    }
+}
+
+/// Picks the side that changed from `base`, or `None` if both changed it differently.
+fn choose<'a, T: ?Sized, F: Fn(&T, &T) -> bool>(
+    base: &'a T,
+    ours: &'a T,
+    theirs: &'a T,
+    eq: F,
+) -> Option<&'a T> {
+    if eq(ours, base) || eq(ours, theirs) {
+        Some(theirs)
+    } else if eq(theirs, base) {
+        Some(ours)
+    } else {
+        None
+    }
+}
+
+/// Merges the three versions of one tier, any of which may be missing.
+fn merge_tier(
+    name: &str,
+    base: Option<&Tier>,
+    ours: Option<&Tier>,
+    theirs: Option<&Tier>,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Tier> {
+    let tier_conflict = |conflicts: &mut Vec<Conflict>, reason: &str| {
+        conflicts.push(Conflict::Tier {
+            tier: name.to_string(),
+            reason: reason.to_string(),
+        });
+    };
+
+    match (base, ours, theirs) {
+        (
+            Some(Tier::IntervalTier(base)),
+            Some(Tier::IntervalTier(ours)),
+            Some(Tier::IntervalTier(theirs)),
+        ) => Some(merge_intervals(name, base, ours, theirs, conflicts).into()),
+        (
+            Some(Tier::PointTier(base)),
+            Some(Tier::PointTier(ours)),
+            Some(Tier::PointTier(theirs)),
+        ) => Some(merge_points(name, base, ours, theirs, conflicts).into()),
+        (Some(base), Some(ours), Some(theirs)) => {
+            let chosen = choose(base, ours, theirs, same_tier);
+            if chosen.is_none() {
+                tier_conflict(conflicts, "changed differently in both versions");
+            }
+            Some(chosen.unwrap_or(ours).clone())
+        }
+        (Some(base), None, Some(edited)) | (Some(base), Some(edited), None) => {
+            if same_tier(base, edited) {
+                None
+            } else {
+                tier_conflict(conflicts, "deleted in one version and edited in the other");
+                Some(edited.clone())
+            }
+        }
+        (None, Some(ours), Some(theirs)) => {
+            if !same_tier(ours, theirs) {
+                tier_conflict(conflicts, "added differently in both versions");
+            }
+            Some(ours.clone())
+        }
+        (None, Some(added), None) | (None, None, Some(added)) => Some(added.clone()),
+        (_, None, None) => None,
+    }
+}
+
+/// Merges the `(xmin, xmax)` of three versions, taking the union of the changes if both sides
+/// changed them differently.
+fn merge_bounds(base: (f64, f64), ours: (f64, f64), theirs: (f64, f64)) -> (f64, f64) {
+    choose(&base, &ours, &theirs, PartialEq::eq).map_or_else(
+        || (ours.0.min(theirs.0), ours.1.max(theirs.1)),
+        |bounds| *bounds,
+    )
+}
+
+/// Merges three versions of an interval tier, stretch by stretch between the boundaries all
+/// three share.
+fn merge_intervals(
+    name: &str,
+    base: &IntervalTier,
+    ours: &IntervalTier,
+    theirs: &IntervalTier,
+    conflicts: &mut Vec<Conflict>,
+) -> IntervalTier {
+    let ours_boundaries = interval_boundaries(ours);
+    let theirs_boundaries = interval_boundaries(theirs);
+    let shared = interval_boundaries(base)
+        .into_iter()
+        .filter(|t| ours_boundaries.contains(t) && theirs_boundaries.contains(t))
+        .collect::<Vec<f64>>();
+
+    let labels = |intervals: &[&Interval]| {
+        intervals
+            .iter()
+            .map(|interval| interval.text().clone())
+            .collect::<Vec<String>>()
+    };
+
+    let mut intervals = Vec::new();
+    for ((base, ours), theirs) in group_intervals(base, &shared)
+        .iter()
+        .zip(&group_intervals(ours, &shared))
+        .zip(&group_intervals(theirs, &shared))
+    {
+        if let Some(chosen) = choose(base, ours, theirs, same_intervals) {
+            intervals.extend(chosen.iter().map(|interval| (*interval).clone()));
+            continue;
+        }
+
+        let edited = ours.iter().chain(theirs);
+        let xmin = edited
+            .clone()
+            .map(|interval| *interval.xmin())
+            .fold(f64::INFINITY, f64::min);
+        let xmax = edited
+            .map(|interval| *interval.xmax())
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let (ours, theirs) = (labels(ours), labels(theirs));
+        intervals.push(Interval::new(xmin, xmax, conflict_marker(&ours, &theirs)));
+        conflicts.push(Conflict::Region {
+            tier: name.to_string(),
+            xmin,
+            xmax,
+            base: labels(base),
+            ours,
+            theirs,
+        });
+    }
+
+    let (xmin, xmax) = merge_bounds(
+        (*base.xmin(), *base.xmax()),
+        (*ours.xmin(), *ours.xmax()),
+        (*theirs.xmin(), *theirs.xmax()),
+    );
+    IntervalTier::new(name.to_string(), xmin, xmax, intervals)
+}
+
+/// Merges three versions of a point tier, time by time.
+fn merge_points(
+    name: &str,
+    base: &PointTier,
+    ours: &PointTier,
+    theirs: &PointTier,
+    conflicts: &mut Vec<Conflict>,
+) -> PointTier {
+    let mut times = base
+        .points()
+        .iter()
+        .chain(ours.points())
+        .chain(theirs.points())
+        .map(|point| *point.number())
+        .collect::<Vec<f64>>();
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+
+    let marks_at = |tier: &PointTier, t: f64| {
+        let start = tier.points().partition_point(|point| *point.number() < t);
+        let end = tier.points().partition_point(|point| *point.number() <= t);
+        tier.points()[start..end]
+            .iter()
+            .map(|point| point.mark().clone())
+            .collect::<Vec<String>>()
+    };
+
+    let mut points = Vec::new();
+    for t in times {
+        let (base, ours, theirs) = (marks_at(base, t), marks_at(ours, t), marks_at(theirs, t));
+
+        if let Some(chosen) = choose(&base, &ours, &theirs, PartialEq::eq) {
+            points.extend(chosen.iter().map(|mark| Point::new(t, mark.clone())));
+            continue;
+        }
+
+        points.push(Point::new(t, conflict_marker(&ours, &theirs)));
+        conflicts.push(Conflict::Region {
+            tier: name.to_string(),
+            xmin: t,
+            xmax: t,
+            base,
+            ours,
+            theirs,
+        });
+    }
+
+    let (xmin, xmax) = merge_bounds(
+        (*base.xmin(), *base.xmax()),
+        (*ours.xmin(), *ours.xmax()),
+        (*theirs.xmin(), *theirs.xmax()),
+    );
+    PointTier::new(name.to_string(), xmin, xmax, points)
+}
+
+/// Returns `true` if two stretches of intervals have the same times and labels.
+fn same_intervals(a: &Vec<&Interval>, b: &Vec<&Interval>) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| (a.xmin(), a.xmax(), a.text()) == (b.xmin(), b.xmax(), b.text()))
+}
+
+/// Returns `true` if two tiers are of the same kind, with the same bounds and annotations.
+fn same_tier(a: &Tier, b: &Tier) -> bool {
+    let bounds = |tier: &Tier| (tier.xmin(), tier.xmax());
+    a.is_interval() == b.is_interval()
+        && a.len() == b.len()
+        && bounds(a) == bounds(b)
+        && a.annotations().eq(b.annotations())
+}
+
+/// Builds the label that marks a conflict, in the style of git's conflict markers.
+fn conflict_marker(ours: &[String], theirs: &[String]) -> String {
+    format!(
+        "<<<<<<< {} ======= {} >>>>>>>",
+        ours.join(" | "),
+        theirs.join(" | ")
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_three_way {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::TextGrid,
+        three_way::Conflict,
+    };
+
+    fn textgrid(words: &[(f64, f64, &str)], tones: &[(f64, &str)]) -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 3.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "words".to_string(),
+                0.0,
+                3.0,
+                words
+                    .iter()
+                    .map(|&(xmin, xmax, text)| Interval::new(xmin, xmax, text.to_string()))
+                    .collect(),
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                tones
+                    .iter()
+                    .map(|&(time, mark)| Point::new(time, mark.to_string()))
+                    .collect(),
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    fn labels(textgrid: &TextGrid) -> Vec<String> {
+        textgrid
+            .interval_tier("words")
+            .unwrap()
+            .intervals()
+            .iter()
+            .map(|interval| interval.text().clone())
+            .collect()
+    }
+
+    const WORDS: [(f64, f64, &str); 3] = [(0.0, 1.0, "a"), (1.0, 2.0, "b"), (2.0, 3.0, "c")];
+
+    #[test]
+    fn different_regions() {
+        let base = textgrid(&WORDS, &[(0.5, "H*")]);
+        let ours = textgrid(
+            &[(0.0, 0.8, "a"), (0.8, 2.0, "b"), (2.0, 3.0, "c")],
+            &[(0.5, "H*")],
+        );
+        let theirs = textgrid(
+            &[(0.0, 1.0, "a"), (1.0, 2.0, "b"), (2.0, 3.0, "see")],
+            &[(0.5, "H*"), (2.5, "L%")],
+        );
+
+        let merge = TextGrid::merge_three_way(&base, &ours, &theirs);
+        assert!(merge.is_clean());
+
+        let words = merge.textgrid().interval_tier("words").unwrap();
+        assert_eq!(*words.intervals()[0].xmax(), 0.8);
+        assert_eq!(labels(merge.textgrid()), vec!["a", "b", "see"]);
+        assert_eq!(
+            merge.textgrid().point_tier("tones").unwrap().points().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn conflicting_region() {
+        let base = textgrid(&WORDS, &[(0.5, "H*")]);
+        let ours = textgrid(
+            &[(0.0, 1.0, "a"), (1.0, 2.0, "bee"), (2.0, 3.0, "c")],
+            &[(0.5, "L*")],
+        );
+        let theirs = textgrid(
+            &[
+                (0.0, 1.0, "a"),
+                (1.0, 1.5, "b1"),
+                (1.5, 2.0, "b2"),
+                (2.0, 3.0, "c"),
+            ],
+            &[(0.5, "H+L*")],
+        );
+
+        let merge = TextGrid::merge_three_way(&base, &ours, &theirs);
+        assert_eq!(
+            labels(merge.textgrid()),
+            vec!["a", "<<<<<<< bee ======= b1 | b2 >>>>>>>", "c"]
+        );
+        assert_eq!(
+            merge.conflicts()[0],
+            Conflict::Region {
+                tier: "words".to_string(),
+                xmin: 1.0,
+                xmax: 2.0,
+                base: vec!["b".to_string()],
+                ours: vec!["bee".to_string()],
+                theirs: vec!["b1".to_string(), "b2".to_string()],
+            }
+        );
+        assert_eq!(
+            merge.textgrid().point_tier("tones").unwrap().points()[0].mark(),
+            "<<<<<<< L* ======= H+L* >>>>>>>"
+        );
+        assert_eq!(merge.conflicts().len(), 2);
+    }
+
+    #[test]
+    fn tiers() {
+        let base = textgrid(&WORDS, &[(0.5, "H*")]);
+        let mut ours = base.clone();
+        ours.delete_tier("tones", false);
+        let mut theirs = textgrid(&WORDS, &[(0.6, "H*")]);
+        theirs.push_tier(
+            IntervalTier::new("notes".to_string(), 0.0, 3.0, Vec::new()).into(),
+            false,
+        );
+
+        let merge = TextGrid::merge_three_way(&base, &ours, &theirs);
+        assert!(merge.textgrid().point_tier("tones").is_some());
+        assert!(merge.textgrid().interval_tier("notes").is_some());
+        assert!(matches!(
+            &merge.conflicts()[..],
+            [Conflict::Tier { tier, .. }] if tier == "tones"
+        ));
+
+        let merge = TextGrid::merge_three_way(&base, &ours, &base);
+        assert!(merge.is_clean());
+        assert!(merge.textgrid().get_tier("tones").is_none());
+    }
+}
@@ -0,0 +1,376 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single annotation on a tier: an interval, or a point, whose `xmin` and `xmax` are equal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Annotation<'a> {
+    index: usize,
+    xmin: f64,
+    xmax: f64,
+    text: &'a str,
+    is_point: bool,
+}
+
+impl<'a> Annotation<'a> {
+    /// Creates an annotation for the interval at `index` on its tier.
+    #[must_use]
+    pub const fn interval(index: usize, xmin: f64, xmax: f64, text: &'a str) -> Self {
+        Self {
+            index,
+            xmin,
+            xmax,
+            text,
+            is_point: false,
+        }
+    }
+
+    /// Creates an annotation for the point at `index` on its tier.
+    #[must_use]
+    pub const fn point(index: usize, time: f64, text: &'a str) -> Self {
+        Self {
+            index,
+            xmin: time,
+            xmax: time,
+            text,
+            is_point: true,
+        }
+    }
+
+    /// Returns the index of the interval or point on its tier.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the start time of the annotation.
+    #[must_use]
+    pub const fn xmin(&self) -> f64 {
+        self.xmin
+    }
+
+    /// Returns the end time of the annotation, equal to `xmin` for points.
+    #[must_use]
+    pub const fn xmax(&self) -> f64 {
+        self.xmax
+    }
+
+    /// Returns the text of an interval or the mark of a point.
+    #[must_use]
+    pub const fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns `true` if the annotation is a point rather than an interval. An interval with
+    /// no duration is still an interval.
+    #[must_use]
+    pub const fn is_point(&self) -> bool {
+        self.is_point
+    }
+
+    /// Returns `true` if the annotation lies within `[t0, t1)` (points) or shares a stretch of
+    /// time with it (intervals). Intervals that only touch the range at one end do not overlap it.
+    #[must_use]
+    pub fn overlaps(&self, t0: f64, t1: f64) -> bool {
+        if self.is_point() {
+            t0 <= self.xmin && self.xmin < t1
+        } else {
+            self.xmin < t1 && self.xmax > t0
+        }
+    }
+
+    /// Returns `true` if two annotations share a stretch of time. Following Praat's convention, a
+    /// point intersects the interval `[xmin, xmax)` it falls in, and two points intersect only if
+    /// they are at the same time.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn intersects(&self, other: &Self) -> bool {
+        match (self.is_point(), other.is_point()) {
+            (false, false) => self.xmin < other.xmax && other.xmin < self.xmax,
+            (true, false) => (other.xmin..other.xmax).contains(&self.xmin),
+            (false, true) => (self.xmin..self.xmax).contains(&other.xmin),
+            (true, true) => self.xmin == other.xmin,
+        }
+    }
+}
+
+/// A problem with the timing of the intervals or points of a tier, found by
+/// `AnnotationTier::check_overlaps`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "issue", rename_all = "snake_case")
+)]
+pub enum TierIssue {
+    /// Nothing covers `[xmin, xmax]`. `before` and `after` are the intervals on either side,
+    /// or `None` at the edges of the tier.
+    Gap {
+        before: Option<usize>,
+        after: Option<usize>,
+        xmin: f64,
+        xmax: f64,
+    },
+    /// The interval `second` starts before the earlier interval `first` ends. The two share
+    /// `[xmin, xmax]`.
+    Overlap {
+        first: usize,
+        second: usize,
+        xmin: f64,
+        xmax: f64,
+    },
+    /// The points `first` and `second` are at the same time, `distance` apart.
+    Duplicate {
+        first: usize,
+        second: usize,
+        time: f64,
+        distance: f64,
+    },
+    /// The interval or point `index` reaches `overshoot` seconds past the tier's bounds.
+    OutOfTierBounds {
+        index: usize,
+        xmin: f64,
+        xmax: f64,
+        overshoot: f64,
+    },
+    /// The interval `index` ends at or before its start.
+    ZeroOrNegativeDuration { index: usize, xmin: f64, xmax: f64 },
+    /// The interval or point `index`, at `xmin`, comes before the previous one, at `xmax`, so
+    /// the tier is not sorted by time.
+    Unsorted { index: usize, xmin: f64, xmax: f64 },
+}
+
+impl TierIssue {
+    /// Returns the size of the issue in seconds: the length of a gap or overlap, the distance
+    /// between duplicates, how far out of bounds an item reaches, how far below zero a
+    /// duration is, or how far back in time an unsorted item goes.
+    #[must_use]
+    pub fn magnitude(&self) -> f64 {
+        match self {
+            Self::Gap { xmin, xmax, .. }
+            | Self::Overlap { xmin, xmax, .. }
+            | Self::Unsorted { xmin, xmax, .. } => xmax - xmin,
+            Self::Duplicate { distance, .. } => *distance,
+            Self::OutOfTierBounds { overshoot, .. } => *overshoot,
+            Self::ZeroOrNegativeDuration { xmin, xmax, .. } => xmin - xmax,
+        }
+    }
+
+    /// Returns the index of the interval or point the issue is reported against: the one
+    /// after a gap, the later of two overlapping or duplicate items, or the offending item.
+    #[must_use]
+    pub const fn index(&self) -> Option<usize> {
+        match self {
+            Self::Gap { after, .. } => *after,
+            Self::Overlap { second, .. } | Self::Duplicate { second, .. } => Some(*second),
+            Self::OutOfTierBounds { index, .. }
+            | Self::ZeroOrNegativeDuration { index, .. }
+            | Self::Unsorted { index, .. } => Some(*index),
+        }
+    }
+
+    /// Returns the stretch of time the issue is about.
+    #[must_use]
+    pub const fn span(&self) -> (f64, f64) {
+        match self {
+            Self::Gap { xmin, xmax, .. }
+            | Self::Overlap { xmin, xmax, .. }
+            | Self::OutOfTierBounds { xmin, xmax, .. }
+            | Self::ZeroOrNegativeDuration { xmin, xmax, .. }
+            | Self::Unsorted { xmin, xmax, .. } => (*xmin, *xmax),
+            Self::Duplicate { time, .. } => (*time, *time),
+        }
+    }
+}
+
+impl Display for TierIssue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Gap { xmin, xmax, .. } => write!(f, "gap from {xmin} to {xmax}"),
+            Self::Overlap {
+                first,
+                second,
+                xmin,
+                xmax,
+            } => write!(
+                f,
+                "interval {second} overlaps interval {first} from {xmin} to {xmax}"
+            ),
+            Self::Duplicate {
+                first,
+                second,
+                time,
+                ..
+            } => write!(f, "point {second} duplicates point {first} at {time}"),
+            Self::OutOfTierBounds {
+                index, overshoot, ..
+            } => write!(f, "item {index} is {overshoot} seconds outside the tier"),
+            Self::ZeroOrNegativeDuration { index, xmin, xmax } => write!(
+                f,
+                "interval {index} has a duration of {} seconds",
+                xmax - xmin
+            ),
+            Self::Unsorted { index, xmin, xmax } => {
+                write!(f, "item {index} at {xmin} comes after an item at {xmax}")
+            }
+        }
+    }
+}
+
+/// Behaviour shared by interval tiers and point tiers, so that code can be written once for
+/// both kinds. Implemented by `interval::Tier`, `point::Tier` and the `textgrid::Tier` enum.
+pub trait AnnotationTier {
+    /// Returns the name of the tier.
+    fn name(&self) -> &str;
+
+    /// Sets the name of the tier.
+    fn set_name(&mut self, name: String);
+
+    /// Returns the start time of the tier.
+    fn xmin(&self) -> f64;
+
+    /// Returns the end time of the tier.
+    fn xmax(&self) -> f64;
+
+    /// Sets the start time of the tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `xmin` - The start time to set.
+    /// * `warn` - If `Some(true)`, displays a warning if any interval or point starts before `xmin`.
+    fn set_xmin(&mut self, xmin: f64, warn: Option<bool>);
+
+    /// Sets the end time of the tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `xmax` - The end time to set.
+    /// * `warn` - If `Some(true)`, displays a warning if any interval or point ends after `xmax`.
+    fn set_xmax(&mut self, xmax: f64, warn: Option<bool>);
+
+    /// Returns the number of intervals or points on the tier.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the tier has no intervals or points.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the intervals or points of the tier, in order.
+    fn annotations(&self) -> Box<dyn Iterator<Item = Annotation<'_>> + '_>;
+
+    /// Returns the intervals sharing a stretch of time with `[t0, t1)` and the points within it,
+    /// as defined by `Annotation::overlaps`.
+    fn annotations_in(&self, t0: f64, t1: f64) -> Vec<Annotation<'_>> {
+        self.annotations()
+            .filter(|annotation| annotation.overlaps(t0, t1))
+            .collect()
+    }
+
+    /// Checks for gaps and overlaps between intervals, duplicate points, intervals or points
+    /// outside the tier's bounds, and intervals or points out of order, in linear time.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - Differences in time of at most `epsilon` are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The issues found, in order of time.
+    fn check_overlaps(&self, epsilon: f64) -> Vec<TierIssue>;
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_tier {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::Tier,
+        tier::AnnotationTier,
+    };
+
+    fn labels_in<T: AnnotationTier>(tier: &T, t0: f64, t1: f64) -> Vec<String> {
+        tier.annotations_in(t0, t1)
+            .iter()
+            .map(|annotation| annotation.text().to_string())
+            .collect()
+    }
+
+    fn interval_tier() -> IntervalTier {
+        IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            3.0,
+            vec![
+                Interval::new(0.0, 1.0, "daisy".to_string()),
+                Interval::new(1.0, 2.0, "daisy".to_string()),
+                Interval::new(2.0, 3.0, "bell".to_string()),
+            ],
+        )
+    }
+
+    fn point_tier() -> PointTier {
+        PointTier::new(
+            "tones".to_string(),
+            0.0,
+            3.0,
+            vec![
+                Point::new(0.5, "H*".to_string()),
+                Point::new(2.0, "L%".to_string()),
+            ],
+        )
+    }
+
+    #[test]
+    fn generic() {
+        assert_eq!(labels_in(&interval_tier(), 1.5, 2.0), vec!["daisy"]);
+        assert_eq!(labels_in(&point_tier(), 1.5, 2.5), vec!["L%"]);
+        assert!(labels_in(&point_tier(), 1.5, 2.0).is_empty());
+        assert_eq!(
+            labels_in(&Tier::PointTier(point_tier()), 0.0, 3.0),
+            vec!["H*", "L%"]
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let mut tier = Tier::IntervalTier(interval_tier());
+
+        assert_eq!(AnnotationTier::name(&tier), "words");
+        assert_eq!(AnnotationTier::len(&tier), 3);
+        assert!(!AnnotationTier::is_empty(&tier));
+
+        AnnotationTier::set_xmax(&mut tier, 4.0, None);
+        AnnotationTier::set_name(&mut tier, "phones".to_string());
+        assert_eq!(AnnotationTier::xmax(&tier), 4.0);
+        assert_eq!(AnnotationTier::name(&tier), "phones");
+    }
+
+    #[test]
+    fn annotations() {
+        let tier = point_tier();
+        let annotations = tier.annotations().collect::<Vec<_>>();
+
+        assert_eq!(annotations.len(), 2);
+        assert!(annotations[0].is_point());
+        assert_eq!(annotations[1].index(), 1);
+        assert_eq!(annotations[1].xmin(), 2.0);
+
+        let zero_length = IntervalTier::new(
+            "words".to_string(),
+            0.0,
+            2.0,
+            vec![
+                Interval::new(0.0, 1.0, "daisy".to_string()),
+                Interval::new(1.0, 1.0, String::new()),
+                Interval::new(1.0, 2.0, "bell".to_string()),
+            ],
+        );
+        let annotations = zero_length.annotations().collect::<Vec<_>>();
+        assert!(!annotations[1].is_point());
+        assert!(!annotations[1].intersects(&annotations[0]));
+        assert!(!annotations[1].overlaps(1.0, 2.0));
+    }
+}
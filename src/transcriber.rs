@@ -0,0 +1,333 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::{
+    input::Source,
+    point::{Point, Tier as PointTier},
+    textgrid::{TextGrid, Tier},
+    utilities::{contiguous_tier, get_file_content},
+};
+
+/// The speaker spans, keyed by speaker id, events and comments collected from the turns of a Transcriber file.
+#[derive(Default)]
+struct Annotations {
+    spans: HashMap<String, Vec<(f64, f64, String)>>,
+    events: Vec<Point>,
+    comments: Vec<Point>,
+}
+
+/// Parses a Transcriber (`.trs`) XML file into a `TextGrid`.
+///
+/// Each speaker gets an `IntervalTier`, named after the speaker's `name` attribute, in the
+/// order the speakers are declared; speakers who share a name get numbered tiers, as with
+/// `TextGrid::push_tier`. Each `Turn` is split into intervals at its `Sync` points,
+/// and the text of overlapping turns is attributed to speakers through their `Who` markers.
+/// `Event` and `Comment` annotations are placed at the time of the preceding `Sync` on the
+/// `events` and `comments` point tiers respectively.
+///
+/// # Arguments
+///
+/// * `input` - Any input accepted by `parse_textgrid`.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, is not well-formed XML, or has a time
+/// attribute that cannot be parsed as a float.
+pub fn parse_trs<I: Into<Source>>(input: I) -> Result<TextGrid> {
+    let (content, name) = get_file_content(input.into())?;
+    let content = content.join("\n");
+
+    // Transcriber writes a `<!DOCTYPE Trans SYSTEM "trans-14.dtd">` declaration
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(&content, options).map_err(|error| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Transcriber malformed; {error}"),
+        )
+    })?;
+
+    // Spans are keyed by speaker id, so speakers who share a name are kept apart
+    let mut speakers = Vec::<(String, String)>::new();
+    for speaker in document
+        .descendants()
+        .filter(|node| node.has_tag_name("Speaker"))
+    {
+        let id = speaker.attribute("id").unwrap_or_default().to_string();
+        if speakers.iter().any(|(declared, _)| *declared == id) {
+            continue;
+        }
+        let name = speaker.attribute("name").unwrap_or(&id).to_string();
+        speakers.push((id, name));
+    }
+
+    let mut xmax = 0.0_f64;
+    let mut annotations = Annotations::default();
+
+    for turn in document
+        .descendants()
+        .filter(|node| node.has_tag_name("Turn"))
+    {
+        xmax = xmax.max(time_attribute(turn, "endTime")?);
+        read_turn(turn, &mut annotations)?;
+    }
+
+    for section in document
+        .descendants()
+        .filter(|node| node.has_tag_name("Section"))
+    {
+        xmax = xmax.max(time_attribute(section, "endTime")?);
+    }
+
+    let mut textgrid = TextGrid::new(0.0, xmax, Vec::new(), name);
+
+    for (id, name) in speakers {
+        let speaker_spans = annotations.spans.remove(&id).unwrap_or_default();
+        textgrid.push_tier(
+            Tier::IntervalTier(contiguous_tier(&name, xmax, speaker_spans)),
+            false,
+        );
+    }
+    textgrid.push_tier(
+        Tier::PointTier(PointTier::new(
+            "events".to_string(),
+            0.0,
+            xmax,
+            annotations.events,
+        )),
+        false,
+    );
+    textgrid.push_tier(
+        Tier::PointTier(PointTier::new(
+            "comments".to_string(),
+            0.0,
+            xmax,
+            annotations.comments,
+        )),
+        false,
+    );
+
+    Ok(textgrid)
+}
+
+/// Reads the text, events and comments of a `Turn` into `annotations`.
+fn read_turn(turn: Node, annotations: &mut Annotations) -> Result<()> {
+    let turn_start = time_attribute(turn, "startTime")?;
+    let turn_end = time_attribute(turn, "endTime")?;
+
+    let speakers = turn
+        .attribute("speaker")
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<String>>();
+
+    let mut texts = vec![String::new(); speakers.len()];
+    let mut who = 0;
+    let mut sync_time = turn_start;
+
+    for child in turn.children() {
+        match child.tag_name().name() {
+            "Sync" => {
+                let time = time_attribute(child, "time")?;
+                flush_texts(
+                    &speakers,
+                    &mut texts,
+                    sync_time,
+                    time,
+                    &mut annotations.spans,
+                );
+                sync_time = time;
+            }
+            "Who" => {
+                who = child
+                    .attribute("nb")
+                    .and_then(|nb| nb.parse::<usize>().ok())
+                    .map_or(0, |nb| nb.saturating_sub(1));
+            }
+            "Event" => {
+                let description = child.attribute("desc").unwrap_or_default();
+                let mark = match child.attribute("extent") {
+                    Some("begin") => format!("[{description}-]"),
+                    Some("end") => format!("[-{description}]"),
+                    _ => format!("[{description}]"),
+                };
+                annotations.events.push(Point::new(sync_time, mark));
+            }
+            "Comment" => {
+                let description = child.attribute("desc").unwrap_or_default();
+                annotations
+                    .comments
+                    .push(Point::new(sync_time, description.to_string()));
+            }
+            _ if child.is_text() => {
+                if let Some(text) = texts.get_mut(who) {
+                    text.push(' ');
+                    text.push_str(child.text().unwrap_or_default());
+                }
+            }
+            _ => {}
+        }
+    }
+    flush_texts(
+        &speakers,
+        &mut texts,
+        sync_time,
+        turn_end,
+        &mut annotations.spans,
+    );
+
+    Ok(())
+}
+
+/// Moves the text collected for each speaker since the last `Sync` into that speaker's spans.
+fn flush_texts(
+    speakers: &[String],
+    texts: &mut [String],
+    start: f64,
+    end: f64,
+    spans: &mut HashMap<String, Vec<(f64, f64, String)>>,
+) {
+    for (speaker, text) in speakers.iter().zip(texts.iter_mut()) {
+        let normalized = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if !normalized.is_empty() && end > start {
+            spans
+                .entry(speaker.clone())
+                .or_default()
+                .push((start, end, normalized));
+        }
+        text.clear();
+    }
+}
+
+/// Parses a time attribute of an element.
+fn time_attribute(node: Node, attribute: &str) -> Result<f64> {
+    let value = node.attribute(attribute).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Transcriber malformed; `{}` is missing `{attribute}`",
+                node.tag_name().name()
+            ),
+        )
+    })?;
+
+    value.parse::<f64>().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Transcriber malformed; could not parse `{attribute}` \"{value}\" as a float"),
+        )
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_transcriber {
+    use crate::{textgrid::Tier, transcriber::parse_trs};
+
+    const TRS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE Trans SYSTEM "trans-14.dtd">
+<Trans audio_filename="daisy" version="1">
+<Speakers>
+<Speaker id="spk1" name="John"/>
+<Speaker id="spk2" name="Kelly"/>
+</Speakers>
+<Episode>
+<Section type="report" startTime="0" endTime="6">
+<Turn speaker="spk1" startTime="0" endTime="3">
+<Sync time="0"/>
+daisy daisy
+<Event desc="breath" type="noise" extent="instantaneous"/>
+<Sync time="1.5"/>
+give me your answer
+<Comment desc="unclear"/>
+</Turn>
+<Turn speaker="spk1 spk2" startTime="3" endTime="5">
+<Sync time="3"/>
+<Who nb="1"/>
+do
+<Who nb="2"/>
+bell
+</Turn>
+</Section>
+</Episode>
+</Trans>
+"#;
+
+    #[test]
+    fn parse() {
+        let textgrid = parse_trs(TRS).unwrap();
+
+        assert_eq!(textgrid.xmax(), &6.0);
+        assert_eq!(textgrid.get_size(), 4);
+
+        let Some(Tier::IntervalTier(john)) = textgrid.get_tier("John") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(john.get_size(), 4);
+        assert_eq!(john.intervals()[0].text(), "daisy daisy");
+        assert_eq!(john.intervals()[1].text(), "give me your answer");
+        assert_eq!(john.intervals()[1].xmin(), &1.5);
+        assert_eq!(john.intervals()[2].text(), "do");
+        assert_eq!(john.intervals()[3].text(), "");
+
+        let Some(Tier::IntervalTier(kelly)) = textgrid.get_tier("Kelly") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(kelly.intervals()[1].text(), "bell");
+        assert_eq!(kelly.intervals()[1].xmin(), &3.0);
+
+        let Some(Tier::PointTier(events)) = textgrid.get_tier("events") else {
+            panic!("Expected PointTier");
+        };
+        assert_eq!(events.points()[0].mark(), "[breath]");
+
+        let Some(Tier::PointTier(comments)) = textgrid.get_tier("comments") else {
+            panic!("Expected PointTier");
+        };
+        assert_eq!(comments.points()[0].number(), &1.5);
+    }
+
+    #[test]
+    fn parse_shared_names() {
+        let trs = r#"<Trans>
+<Speakers>
+<Speaker id="spk1" name="John"/>
+<Speaker id="spk2" name="John"/>
+</Speakers>
+<Episode>
+<Section type="report" startTime="0" endTime="2">
+<Turn speaker="spk1" startTime="0" endTime="1"><Sync time="0"/>daisy</Turn>
+<Turn speaker="spk2" startTime="1" endTime="2"><Sync time="1"/>bell</Turn>
+</Section>
+</Episode>
+</Trans>"#;
+        let textgrid = parse_trs(trs).unwrap();
+
+        assert_eq!(textgrid.get_size(), 4);
+
+        let Some(Tier::IntervalTier(first)) = textgrid.get_tier("John") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(first.intervals()[0].text(), "daisy");
+        assert_eq!(first.intervals()[1].text(), "");
+
+        let Some(Tier::IntervalTier(second)) = textgrid.get_tier("John1") else {
+            panic!("Expected IntervalTier");
+        };
+        assert_eq!(second.intervals()[0].text(), "");
+        assert_eq!(second.intervals()[1].text(), "bell");
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse_trs("<Trans><Turn startTime=\"zero\" endTime=\"1\"/></Trans>").is_err());
+        assert!(parse_trs("<Trans>").is_err());
+    }
+}
@@ -7,7 +7,10 @@ use std::{
 
 use regex::Regex;
 
-use crate::input::Source;
+use crate::{
+    input::Source,
+    interval::{Interval, Tier as IntervalTier},
+};
 
 /// Pull the next number from the `VecDeque` of `String`s.
 ///
@@ -74,7 +77,7 @@ pub fn process_lines(lines: &[String]) -> Vec<String> {
             .chars()
             .all(|character| character.is_numeric() || character == '.')
         {
-            processed_lines.push(line.to_string());
+            processed_lines.push(line.clone());
         }
     }
 
@@ -172,7 +175,143 @@ pub fn get_file_content(source: Source) -> Result<(Vec<String>, String)> {
     }
 }
 
+/// Builds an interval tier spanning `0.0..xmax` from `(start, end, text)` spans, filling
+/// gaps with empty intervals. A span that overlaps an earlier one is cut to start where the
+/// earlier one ends, and a span that would be left with no duration, such as one nested inside
+/// an earlier span, is skipped.
+///
+/// # Arguments
+///
+/// * `name` - The name of the tier.
+/// * `xmax` - The end of the tier.
+/// * `spans` - The spans to turn into intervals, in any order.
+///
+/// # Returns
+///
+/// An `IntervalTier` with no gaps or overlaps between `0.0` and `xmax`.
+pub fn contiguous_tier(name: &str, xmax: f64, spans: Vec<(f64, f64, String)>) -> IntervalTier {
+    contiguous_tier_with(
+        name,
+        xmax,
+        spans
+            .into_iter()
+            .map(|(start, end, label)| (start, end, label, ()))
+            .collect(),
+    )
+    .0
+}
+
+/// Builds an interval tier as in `contiguous_tier`, from spans that each carry a value, such
+/// as a confidence score.
+///
+/// # Returns
+///
+/// The `IntervalTier`, and the value of each of its intervals: `None` for the empty intervals
+/// filling gaps.
+pub fn contiguous_tier_with<T>(
+    name: &str,
+    xmax: f64,
+    mut spans: Vec<(f64, f64, String, T)>,
+) -> (IntervalTier, Vec<Option<T>>) {
+    spans.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut intervals = Vec::<Interval>::new();
+    let mut values = Vec::<Option<T>>::new();
+    let mut cursor = 0.0;
+
+    for (start, end, label, value) in spans {
+        if end <= start.max(cursor) {
+            continue;
+        }
+        if start > cursor {
+            intervals.push(Interval::new(cursor, start, String::new()));
+            values.push(None);
+        }
+        intervals.push(Interval::new(start.max(cursor), end, label));
+        values.push(Some(value));
+        cursor = end;
+    }
+    if cursor < xmax {
+        intervals.push(Interval::new(cursor, xmax, String::new()));
+        values.push(None);
+    }
+
+    (
+        IntervalTier::new(name.to_string(), 0.0, xmax, intervals),
+        values,
+    )
+}
+
+/// Checks that a time scaling factor is positive and finite, so that scaling keeps every
+/// interval and point in order.
+///
+/// # Errors
+///
+/// Returns an error if `factor` is zero, negative, infinite or NaN.
+pub fn check_scale_factor(factor: f64) -> Result<()> {
+    if factor > 0.0 && factor.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Scale factor must be positive and finite, got {factor}"),
+        ))
+    }
+}
+
+/// Returns every distinct interval boundary of a tier, in order, including its edges.
+pub fn interval_boundaries(tier: &IntervalTier) -> Vec<f64> {
+    let mut times = tier
+        .intervals()
+        .iter()
+        .flat_map(|interval| [*interval.xmin(), *interval.xmax()])
+        .collect::<Vec<f64>>();
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+    times
+}
+
+/// Groups the intervals of a tier by the stretch between consecutive `anchors` they start in.
+pub fn group_intervals<'a>(tier: &'a IntervalTier, anchors: &[f64]) -> Vec<Vec<&'a Interval>> {
+    let mut regions = vec![Vec::new(); anchors.len() + 1];
+    for interval in tier.intervals() {
+        let region = anchors.partition_point(|anchor| *anchor <= *interval.xmin());
+        regions[region].push(interval);
+    }
+    regions
+}
+
+/// Pairs up the times of two sorted lists that are at most `tolerance` apart, in order.
+pub fn match_times(old: &[f64], new: &[f64], tolerance: f64) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if (old[i] - new[j]).abs() <= tolerance {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if old[i] < new[j] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
+
+/// Quotes a CSV field if it contains a comma, quote or line break.
+pub fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod test_utilities {
     use crate::{input::Source, utilities};
     use std::{collections::VecDeque, io::Cursor};
@@ -216,4 +355,26 @@ mod test_utilities {
         assert_eq!(content, expected_content);
         assert_eq!(name, expected_name);
     }
+
+    #[test]
+    fn contiguous_tier() {
+        let spans = vec![
+            (0.5, 1.0, "B".to_string()),
+            (0.0, 2.0, "A".to_string()),
+            (1.5, 2.5, "C".to_string()),
+            (3.0, 3.0, "D".to_string()),
+        ];
+        let tier = utilities::contiguous_tier("test", 4.0, spans);
+
+        let intervals = tier
+            .intervals()
+            .iter()
+            .map(|interval| (*interval.xmin(), *interval.xmax(), interval.text().as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            intervals,
+            vec![(0.0, 2.0, "A"), (2.0, 2.5, "C"), (2.5, 4.0, "")]
+        );
+        assert!(tier.check_overlaps(0.0).is_empty());
+    }
 }
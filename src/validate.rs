@@ -0,0 +1,679 @@
+use std::fmt::{self, Display, Formatter};
+#[cfg(feature = "serde")]
+use std::io::{Error, ErrorKind, Result};
+
+use getset::Getters;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    textgrid::{TextGrid, Tier},
+    tier::{AnnotationTier, TierIssue},
+};
+
+/// How serious a rule violation is.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A violation of a rule found in one tier, before the `Validator` gives it a severity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    /// The index of the offending interval or point, if the violation is about one.
+    pub index: Option<usize>,
+    /// The start of the stretch of time the violation is about.
+    pub xmin: f64,
+    /// The end of the stretch of time the violation is about.
+    pub xmax: f64,
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl Finding {
+    /// Creates a finding about the interval or point at `index`.
+    #[must_use]
+    pub const fn new(index: Option<usize>, xmin: f64, xmax: f64, message: String) -> Self {
+        Self {
+            index,
+            xmin,
+            xmax,
+            message,
+        }
+    }
+}
+
+/// A check of one annotation convention. Implement this to add project-specific rules to a
+/// `Validator`.
+pub trait Rule {
+    /// A short `snake_case` name for the rule, used in reports.
+    fn name(&self) -> &str;
+
+    /// Checks one tier of a `TextGrid`, returning every violation found.
+    fn check(&self, textgrid: &TextGrid, tier: &Tier) -> Vec<Finding>;
+}
+
+/// The rules that come with the crate.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "rule", rename_all = "snake_case")
+)]
+pub enum BuiltinRule {
+    /// Interval tiers have no gaps between intervals or at their edges.
+    NoGaps,
+    /// Interval tiers have no overlapping intervals, and are sorted by time.
+    NoOverlaps,
+    /// Tiers span exactly the same time as the `TextGrid`.
+    BoundsMatchGrid,
+    /// Every non-empty label is one of `labels`.
+    LabelInventory { labels: Vec<String> },
+    /// No label starts or ends with whitespace.
+    NoSurroundingWhitespace,
+    /// Every interval lasts at least `duration` seconds.
+    MinimumDuration { duration: f64 },
+    /// No label is empty.
+    NoEmptyLabels,
+    /// The points of point tiers are at strictly increasing times.
+    StrictlyIncreasingPoints,
+}
+
+impl Rule for BuiltinRule {
+    fn name(&self) -> &str {
+        match self {
+            Self::NoGaps => "no_gaps",
+            Self::NoOverlaps => "no_overlaps",
+            Self::BoundsMatchGrid => "bounds_match_grid",
+            Self::LabelInventory { .. } => "label_inventory",
+            Self::NoSurroundingWhitespace => "no_surrounding_whitespace",
+            Self::MinimumDuration { .. } => "minimum_duration",
+            Self::NoEmptyLabels => "no_empty_labels",
+            Self::StrictlyIncreasingPoints => "strictly_increasing_points",
+        }
+    }
+
+    fn check(&self, textgrid: &TextGrid, tier: &Tier) -> Vec<Finding> {
+        let annotations_where = |predicate: &dyn Fn(&str) -> Option<String>| {
+            tier.annotations()
+                .filter_map(|annotation| {
+                    predicate(annotation.text()).map(|message| {
+                        Finding::new(
+                            Some(annotation.index()),
+                            annotation.xmin(),
+                            annotation.xmax(),
+                            message,
+                        )
+                    })
+                })
+                .collect()
+        };
+
+        match self {
+            Self::NoGaps | Self::NoOverlaps => check_continuity(tier, self == &Self::NoGaps),
+            Self::BoundsMatchGrid => {
+                let bounds = (tier.xmin(), tier.xmax());
+                if bounds == (*textgrid.xmin(), *textgrid.xmax()) {
+                    Vec::new()
+                } else {
+                    vec![Finding::new(
+                        None,
+                        bounds.0,
+                        bounds.1,
+                        format!(
+                            "tier spans [{}, {}] but the TextGrid spans [{}, {}]",
+                            bounds.0,
+                            bounds.1,
+                            textgrid.xmin(),
+                            textgrid.xmax()
+                        ),
+                    )]
+                }
+            }
+            Self::LabelInventory { labels } => annotations_where(&|text| {
+                (!text.is_empty() && !labels.iter().any(|label| label == text))
+                    .then(|| format!("\"{text}\" is not in the label inventory"))
+            }),
+            Self::NoSurroundingWhitespace => annotations_where(&|text| {
+                (text.trim() != text).then(|| format!("\"{text}\" has surrounding whitespace"))
+            }),
+            Self::MinimumDuration { duration } => tier
+                .annotations()
+                .filter(|annotation| {
+                    tier.is_interval() && annotation.xmax() - annotation.xmin() < *duration
+                })
+                .map(|annotation| {
+                    Finding::new(
+                        Some(annotation.index()),
+                        annotation.xmin(),
+                        annotation.xmax(),
+                        format!(
+                            "lasts {} seconds, less than {duration}",
+                            annotation.xmax() - annotation.xmin()
+                        ),
+                    )
+                })
+                .collect(),
+            Self::NoEmptyLabels => {
+                annotations_where(&|text| text.is_empty().then(|| "label is empty".to_string()))
+            }
+            Self::StrictlyIncreasingPoints => tier
+                .as_point()
+                .map(|point_tier| {
+                    point_tier
+                        .points()
+                        .windows(2)
+                        .enumerate()
+                        .filter(|(_, pair)| pair[1].number() <= pair[0].number())
+                        .map(|(index, pair)| {
+                            Finding::new(
+                                Some(index + 1),
+                                *pair[1].number(),
+                                *pair[1].number(),
+                                format!(
+                                    "point at {} does not come after the point at {}",
+                                    pair[1].number(),
+                                    pair[0].number()
+                                ),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Finds the gaps, or the overlaps and unsorted intervals, of an interval tier.
+fn check_continuity(tier: &Tier, gaps: bool) -> Vec<Finding> {
+    tier.check_overlaps(0.0)
+        .into_iter()
+        .filter(|issue| {
+            if gaps {
+                matches!(issue, TierIssue::Gap { .. })
+            } else {
+                matches!(issue, TierIssue::Overlap { .. })
+                    || tier.is_interval() && matches!(issue, TierIssue::Unsorted { .. })
+            }
+        })
+        .map(|issue| {
+            let (xmin, xmax) = issue.span();
+            Finding::new(issue.index(), xmin, xmax, issue.to_string())
+        })
+        .collect()
+}
+
+/// A rule violation found by a `Validator`.
+#[derive(Clone, Debug, Getters, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Issue {
+    /// The name of the rule that was violated.
+    #[getset(get = "pub")]
+    rule: String,
+    /// The severity the `Validator` gives the rule.
+    #[getset(get = "pub")]
+    severity: Severity,
+    /// The name of the tier the violation was found in.
+    #[getset(get = "pub")]
+    tier: String,
+    /// The index of the offending interval or point, if the violation is about one.
+    #[getset(get = "pub")]
+    index: Option<usize>,
+    /// The start of the stretch of time the violation is about.
+    #[getset(get = "pub")]
+    xmin: f64,
+    /// The end of the stretch of time the violation is about.
+    #[getset(get = "pub")]
+    xmax: f64,
+    /// A description of the violation.
+    #[getset(get = "pub")]
+    message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} [{}] `{}`", self.severity, self.rule, self.tier)?;
+        if let Some(index) = self.index {
+            write!(f, " #{index}")?;
+        }
+        write!(f, " [{}, {}]: {}", self.xmin, self.xmax, self.message)
+    }
+}
+
+/// The result of `Validator::validate`.
+#[derive(Clone, Debug, Default, Getters, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationReport {
+    /// The violations, ordered by rule and then by tier.
+    #[getset(get = "pub")]
+    issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no rule with `Severity::Error` was violated.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.max_severity() < Some(Severity::Error)
+    }
+
+    /// Returns the severity of the most serious violation, or `None` if there are none.
+    #[must_use]
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|issue| issue.severity).max()
+    }
+
+    /// Counts the violations of a given severity.
+    #[must_use]
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A rule of a `Validator`, with the severity of its violations and the tiers it applies to.
+struct ConfiguredRule {
+    rule: Box<dyn Rule>,
+    severity: Severity,
+    tiers: Vec<String>,
+}
+
+/// One rule in a validator configuration file.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RuleConfig {
+    #[serde(flatten)]
+    rule: BuiltinRule,
+    #[serde(default)]
+    severity: Severity,
+    #[serde(default)]
+    tiers: Vec<String>,
+}
+
+/// A validator configuration file.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct ValidatorConfig {
+    rules: Vec<RuleConfig>,
+}
+
+/// Checks `TextGrid`s against a set of annotation conventions.
+///
+/// Rules are added one by one with `add_rule`, or loaded from a configuration file with
+/// `from_json` or `from_toml`. A configuration lists the built-in rules by name, each with an
+/// optional severity (`warning` by default) and an optional list of tiers to apply to (all
+/// tiers by default). In TOML:
+///
+/// ```toml
+/// [[rules]]
+/// rule = "no_gaps"
+/// severity = "error"
+///
+/// [[rules]]
+/// rule = "label_inventory"
+/// labels = ["a", "e", "i", "o", "u"]
+/// tiers = ["phones"]
+/// ```
+#[derive(Default)]
+pub struct Validator {
+    rules: Vec<ConfiguredRule>,
+}
+
+impl Validator {
+    /// Creates a validator with no rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule to the validator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule, either a `BuiltinRule` or a custom `Rule`.
+    /// * `severity` - The severity of its violations.
+    /// * `tiers` - The names of the tiers to check, or all tiers if empty.
+    pub fn add_rule<R: Rule + 'static>(
+        &mut self,
+        rule: R,
+        severity: Severity,
+        tiers: Vec<String>,
+    ) -> &mut Self {
+        self.rules.push(ConfiguredRule {
+            rule: Box::new(rule),
+            severity,
+            tiers,
+        });
+        self
+    }
+
+    /// Returns the number of rules in the validator.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns `true` if the validator has no rules.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Creates a validator from a JSON configuration, as described on `Validator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is malformed or names an unknown rule.
+    #[cfg(feature = "serde")]
+    pub fn from_json(config: &str) -> Result<Self> {
+        let config = serde_json::from_str::<ValidatorConfig>(config)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        Ok(Self::from_config(config))
+    }
+
+    /// Creates a validator from a TOML configuration, as described on `Validator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is malformed or names an unknown rule.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(config: &str) -> Result<Self> {
+        let config = toml::from_str::<ValidatorConfig>(config)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        Ok(Self::from_config(config))
+    }
+
+    #[cfg(feature = "serde")]
+    fn from_config(config: ValidatorConfig) -> Self {
+        let mut validator = Self::new();
+        for entry in config.rules {
+            validator.add_rule(entry.rule, entry.severity, entry.tiers);
+        }
+        validator
+    }
+
+    /// Checks a `TextGrid` against every rule.
+    #[must_use]
+    pub fn validate(&self, textgrid: &TextGrid) -> ValidationReport {
+        let mut issues = Vec::new();
+
+        for configured in &self.rules {
+            let tiers = textgrid.tiers().iter().filter(|tier| {
+                configured.tiers.is_empty()
+                    || configured.tiers.iter().any(|name| name == tier.name())
+            });
+            for tier in tiers {
+                issues.extend(
+                    configured
+                        .rule
+                        .check(textgrid, tier)
+                        .into_iter()
+                        .map(|finding| Issue {
+                            rule: configured.rule.name().to_string(),
+                            severity: configured.severity,
+                            tier: tier.name().to_string(),
+                            index: finding.index,
+                            xmin: finding.xmin,
+                            xmax: finding.xmax,
+                            message: finding.message,
+                        }),
+                );
+            }
+        }
+
+        ValidationReport { issues }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod test_validate {
+    use crate::{
+        interval::{Interval, Tier as IntervalTier},
+        point::{Point, Tier as PointTier},
+        textgrid::{TextGrid, Tier},
+        validate::{BuiltinRule, Finding, Rule, Severity, Validator},
+    };
+
+    fn textgrid() -> TextGrid {
+        let mut textgrid = TextGrid::new(0.0, 4.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                4.0,
+                vec![
+                    Interval::new(0.0, 1.0, "a".to_string()),
+                    Interval::new(1.5, 2.5, "x ".to_string()),
+                    Interval::new(2.0, 2.01, String::new()),
+                    Interval::new(2.01, 4.0, "e".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid.push_tier(
+            PointTier::new(
+                "tones".to_string(),
+                0.0,
+                3.0,
+                vec![
+                    Point::new(1.0, "H*".to_string()),
+                    Point::new(1.0, "L%".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        textgrid
+    }
+
+    fn rules(validator: &Validator, textgrid: &TextGrid) -> Vec<(String, String, Option<usize>)> {
+        validator
+            .validate(textgrid)
+            .issues()
+            .iter()
+            .map(|issue| (issue.rule().clone(), issue.tier().clone(), *issue.index()))
+            .collect()
+    }
+
+    #[test]
+    fn builtin_rules() {
+        let textgrid = textgrid();
+        let mut validator = Validator::new();
+        validator
+            .add_rule(BuiltinRule::NoGaps, Severity::Error, Vec::new())
+            .add_rule(BuiltinRule::NoOverlaps, Severity::Error, Vec::new())
+            .add_rule(BuiltinRule::BoundsMatchGrid, Severity::Warning, Vec::new())
+            .add_rule(
+                BuiltinRule::LabelInventory {
+                    labels: vec!["a".to_string(), "e".to_string()],
+                },
+                Severity::Warning,
+                vec!["phones".to_string()],
+            )
+            .add_rule(
+                BuiltinRule::NoSurroundingWhitespace,
+                Severity::Info,
+                Vec::new(),
+            )
+            .add_rule(
+                BuiltinRule::MinimumDuration { duration: 0.05 },
+                Severity::Warning,
+                Vec::new(),
+            )
+            .add_rule(BuiltinRule::NoEmptyLabels, Severity::Info, Vec::new())
+            .add_rule(
+                BuiltinRule::StrictlyIncreasingPoints,
+                Severity::Error,
+                Vec::new(),
+            );
+
+        let found = rules(&validator, &textgrid);
+        let expected = [
+            ("no_gaps", "phones", Some(1)),
+            ("no_overlaps", "phones", Some(2)),
+            ("no_overlaps", "phones", Some(3)),
+            ("bounds_match_grid", "tones", None),
+            ("label_inventory", "phones", Some(1)),
+            ("no_surrounding_whitespace", "phones", Some(1)),
+            ("minimum_duration", "phones", Some(2)),
+            ("no_empty_labels", "phones", Some(2)),
+            ("strictly_increasing_points", "tones", Some(1)),
+        ]
+        .map(|(rule, tier, index)| (rule.to_string(), tier.to_string(), index));
+        assert_eq!(found, expected);
+
+        let report = validator.validate(&textgrid);
+        assert!(!report.is_valid());
+        assert_eq!(report.count(Severity::Info), 2);
+        assert_eq!(*report.issues()[0].xmin(), 1.0);
+        assert_eq!(*report.issues()[0].xmax(), 1.5);
+    }
+
+    #[test]
+    fn minimum_duration_zero_length() {
+        let mut textgrid = TextGrid::new(0.0, 2.0, Vec::new(), "test".to_string());
+        textgrid.push_tier(
+            IntervalTier::new(
+                "phones".to_string(),
+                0.0,
+                2.0,
+                vec![
+                    Interval::new(0.0, 1.0, "a".to_string()),
+                    Interval::new(1.0, 1.0, "x".to_string()),
+                    Interval::new(1.0, 2.0, "e".to_string()),
+                ],
+            )
+            .into(),
+            false,
+        );
+        let mut validator = Validator::new();
+        validator.add_rule(
+            BuiltinRule::MinimumDuration { duration: 0.05 },
+            Severity::Warning,
+            Vec::new(),
+        );
+
+        let found = rules(&validator, &textgrid);
+        assert_eq!(
+            found,
+            vec![(
+                "minimum_duration".to_string(),
+                "phones".to_string(),
+                Some(1)
+            )]
+        );
+    }
+
+    struct NoTonesBefore(f64);
+
+    impl Rule for NoTonesBefore {
+        fn name(&self) -> &'static str {
+            "no_tones_before"
+        }
+
+        fn check(&self, _: &TextGrid, tier: &Tier) -> Vec<Finding> {
+            tier.as_point()
+                .into_iter()
+                .flat_map(|tier| tier.points().iter().enumerate())
+                .filter(|(_, point)| *point.number() < self.0)
+                .map(|(index, point)| {
+                    Finding::new(Some(index), *point.number(), *point.number(), String::new())
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn custom_rule() {
+        let mut validator = Validator::new();
+        validator.add_rule(NoTonesBefore(1.5), Severity::Info, Vec::new());
+
+        let report = validator.validate(&textgrid());
+        assert_eq!(report.issues().len(), 2);
+        assert!(report.is_valid());
+        assert_eq!(report.max_severity(), Some(Severity::Info));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn from_json() {
+        let validator = Validator::from_json(
+            r#"{"rules": [
+                {"rule": "no_gaps", "severity": "error"},
+                {"rule": "minimum_duration", "duration": 0.05, "tiers": ["tones"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(validator.len(), 2);
+        assert_eq!(
+            rules(&validator, &textgrid()),
+            vec![("no_gaps".to_string(), "phones".to_string(), Some(1))]
+        );
+        assert!(Validator::from_json(r#"{"rules": [{"rule": "unknown"}]}"#).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn from_toml() {
+        let validator = Validator::from_toml(
+            r#"
+            [[rules]]
+            rule = "label_inventory"
+            labels = ["a", "e"]
+            tiers = ["phones"]
+
+            [[rules]]
+            rule = "strictly_increasing_points"
+            severity = "error"
+            "#,
+        )
+        .unwrap();
+
+        let report = validator.validate(&textgrid());
+        assert_eq!(report.issues().len(), 2);
+        assert_eq!(*report.issues()[0].severity(), Severity::Warning);
+        assert!(!report.is_valid());
+    }
+}